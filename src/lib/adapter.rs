@@ -0,0 +1,393 @@
+use anyhow::{Context, Result};
+use log::{info, warn};
+use soup::{NodeExt, QueryBuilderExt};
+
+use crate::error::missing;
+
+/// Plant page data before attribute classification and image download
+#[derive(Debug, PartialEq)]
+pub struct PlantPage {
+    /// Plant name from the page title
+    pub name: String,
+    /// Image link as found on the page, may be relative
+    pub image_url: String,
+    /// Parameter/value rows of the plant table
+    pub rows: Vec<PlantRow>,
+}
+
+/// Parameter/value row of a plant table
+#[derive(Debug, PartialEq)]
+pub struct PlantRow {
+    pub parameter: String,
+    pub value: String,
+    /// Value with list items as "• item", used if the row goes to
+    /// `features`. `None` falls back to `value`.
+    pub features_value: Option<String>,
+}
+
+/// Site-specific markup, set with `Scraper::with_adapter`. Returned links
+/// may be relative, `Scraper` resolves them against the site root.
+pub trait SiteAdapter: Send + Sync {
+    /// Category links from the title page
+    fn category_urls(&self, html: &str) -> Vec<String>;
+
+    /// Number of list pages in a category, from its first page
+    fn page_count(&self, _html: &str) -> usize {
+        1
+    }
+
+    /// URL of list page `page` of a category, counted from 1
+    fn page_url(&self, category_url: &str, page: usize) -> String;
+
+    /// Link to the next list page, for layouts without a numeric pager
+    fn next_page_url(&self, _html: &str) -> Option<String> {
+        None
+    }
+
+    /// Plant page links from a category list page
+    fn plant_urls(&self, html: &str) -> Vec<String>;
+
+    /// Name, image link and table rows of a plant page
+    fn parse_plant(&self, html: &str, url: &str) -> Result<PlantPage>;
+
+    /// Pages with fewer rows matched to a known attribute, `features` not
+    /// counted, are rejected as not plant pages. No check by default.
+    fn min_known_attributes(&self) -> usize {
+        0
+    }
+
+    /// Plant name alone, for layouts where the full parse is expensive
+    fn plant_name(&self, html: &str, url: &str) -> Result<String> {
+        Ok(self.parse_plant(html, url)?.name)
+    }
+}
+
+/// WordPress markup of komnatnie-rastenija.ru
+#[derive(Debug, Clone)]
+pub struct KomnatnieRasteniaAdapter {
+    category_classes: Vec<String>,
+}
+
+impl KomnatnieRasteniaAdapter {
+    /// CSS class of the category list items on the title page,
+    /// `cat-item` by default.
+    pub fn with_category_class(self, class: &str) -> Self {
+        self.with_category_classes(vec![class.to_string()])
+    }
+
+    /// Candidate classes of the category list items, tried in order until
+    /// one finds categories, e.g. the classes of the old and new theme.
+    pub fn with_category_classes(mut self, classes: Vec<String>) -> Self {
+        self.category_classes = classes;
+        self
+    }
+}
+
+impl Default for KomnatnieRasteniaAdapter {
+    fn default() -> Self {
+        Self {
+            category_classes: vec!["cat-item".to_string()],
+        }
+    }
+}
+
+impl SiteAdapter for KomnatnieRasteniaAdapter {
+    fn category_urls(&self, html: &str) -> Vec<String> {
+        // Categories ('Рубрики')
+        let soup = soup::Soup::new(html);
+        for (i, class) in self.category_classes.iter().enumerate() {
+            let urls = soup
+                .class(class.as_str())
+                .find_all()
+                .filter_map(|node| node.children().next())
+                .filter_map(|node| node.get("href"))
+                .collect::<Vec<_>>();
+            if urls.is_empty() {
+                continue;
+            }
+            // Matches of a fallback class hint at a theme change
+            if i > 0 {
+                info!("Категории найдены по классу {}", class);
+            }
+            return urls;
+        }
+        Vec::new()
+    }
+
+    fn page_count(&self, html: &str) -> usize {
+        let soup = soup::Soup::new(html);
+        // Take the largest numeric link, ignoring "next"/"prev" and dots
+        soup.attr("class", "nav-links")
+            .find()
+            .and_then(|node| {
+                node.children()
+                    .filter_map(|child| child.text().trim().parse::<usize>().ok())
+                    .max()
+            })
+            .unwrap_or(1)
+            .max(1)
+    }
+
+    fn page_url(&self, category_url: &str, page: usize) -> String {
+        // Treat the category as a directory, so a trailing slash doesn't
+        // produce `cat//page/2`
+        let category_url = format!("{}/", category_url.trim_end_matches('/'));
+        let page = format!("page/{}", page);
+        url::Url::parse(&category_url)
+            .and_then(|base| base.join(&page))
+            .map(|url| url.to_string())
+            .unwrap_or_else(|_| category_url + &page)
+    }
+
+    fn next_page_url(&self, html: &str) -> Option<String> {
+        let soup = soup::Soup::new(html);
+        soup.attr("rel", "next")
+            .find()
+            .and_then(|node| node.get("href"))
+    }
+
+    fn plant_urls(&self, html: &str) -> Vec<String> {
+        let soup = soup::Soup::new(html);
+        soup.tag("a")
+            .attr("itemprop", "url")
+            .find_all()
+            .filter_map(|a| a.get("href"))
+            .collect()
+    }
+
+    fn plant_name(&self, html: &str, url: &str) -> Result<String> {
+        let soup = soup::Soup::new(html);
+        let title = soup
+            .attr("class", "entry-title")
+            .find()
+            .ok_or_else(|| missing("title"))
+            .with_context(|| format!("Can't parse plant page {}", url))?
+            .text();
+        Ok(strip_title_suffix(&title))
+    }
+
+    // The table is found by its "полив" cell, so every parsed page has
+    // one; a second one rejects articles and tag pages quoting a lone
+    // watering tip, while even sparse plant tables add temperature or
+    // lighting
+    fn min_known_attributes(&self) -> usize {
+        2
+    }
+
+    fn parse_plant(&self, html: &str, url: &str) -> Result<PlantPage> {
+        let name = self.plant_name(html, url)?;
+        let soup = soup::Soup::new(html);
+
+        // Parse image url
+        let image_url = soup
+            .attr("itemprop", "url image")
+            .find()
+            .ok_or_else(|| missing("image"))
+            .and_then(|node| node.get("data-src").ok_or_else(|| missing("image url")))
+            .with_context(|| format!("Can't parse plant page {}", url))?;
+
+        // Parse table
+        let node = soup
+            .tag("td")
+            .find_all()
+            .find(|node| node.text().to_lowercase().contains("полив"))
+            .ok_or_else(|| missing("plant table"))?;
+        // Parse table's rows
+        let body = node
+            .parent()
+            .and_then(|tr| tr.parent())
+            .ok_or_else(|| missing("table body"))?;
+        let nodes = body.children().filter(|node| node.name() == "tr");
+        // Take first two non-empty cells, skip rows that have less
+        let rows = nodes
+            .filter_map(|tr| {
+                let mut cells = tr
+                    .children()
+                    .filter(|node| node.name() == "td" || node.name() == "th")
+                    .map(|td| (cell_text(&td, false), td))
+                    .filter(|(text, _)| !text.trim().is_empty());
+                match (cells.next(), cells.next()) {
+                    (Some((parameter, _)), Some((value, td))) => Some(PlantRow {
+                        parameter,
+                        value,
+                        features_value: Some(cell_text(&td, true)),
+                    }),
+                    _ => {
+                        warn!("Пропущена строка таблицы {}: {:?}", url, tr.text());
+                        None
+                    }
+                }
+            })
+            .collect();
+
+        Ok(PlantPage {
+            name,
+            image_url,
+            rows,
+        })
+    }
+}
+
+// Drop the description after a dash in "Name — description" titles.
+// A hyphen only separates when spaced, to keep names like "Ванда-Мокара"
+fn strip_title_suffix(title: &str) -> String {
+    let end = title
+        .find(['—', '–', '‒', '―'])
+        .into_iter()
+        .chain(title.find(" - "))
+        .min()
+        .unwrap_or(title.len());
+    title[..end].trim().to_string()
+}
+
+// Like `text()`, but list items and line breaks are separated by spaces
+// instead of being glued together. With `bullets` list items become
+// "• item", as in the catch-all features cell.
+fn cell_text<N: NodeExt + QueryBuilderExt>(node: &N, bullets: bool) -> String {
+    let mut text = String::new();
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(&child.text());
+        } else if !child.is_element() {
+            continue;
+        } else if child.name() == "li" {
+            text.push_str(if bullets { " • " } else { " " });
+            text.push_str(&cell_text(&child, bullets));
+            text.push(' ');
+        } else if child.name() == "br" {
+            text.push(' ');
+        } else {
+            text.push_str(&cell_text(&child, bullets));
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn page_count_without_nav_links() {
+        let adapter = KomnatnieRasteniaAdapter::default();
+        assert_eq!(
+            adapter.page_count("<html><body><p>Список</p></body></html>"),
+            1
+        );
+    }
+
+    #[test]
+    fn page_count_single_numeric_link() {
+        let adapter = KomnatnieRasteniaAdapter::default();
+        let html = r#"<div class="nav-links"><span class="page-numbers current">1</span></div>"#;
+        assert_eq!(adapter.page_count(html), 1);
+    }
+
+    #[test]
+    fn page_count_with_prev_and_next_links() {
+        let adapter = KomnatnieRasteniaAdapter::default();
+        let html = r#"<div class="nav-links">
+            <a class="prev page-numbers" href="/cat/">« Предыдущая</a>
+            <a class="page-numbers" href="/cat/">1</a>
+            <span class="page-numbers current">2</span>
+            <a class="page-numbers" href="/cat/page/3/">3</a>
+            <a class="next page-numbers" href="/cat/page/3/">Следующая »</a>
+        </div>"#;
+        assert_eq!(adapter.page_count(html), 3);
+    }
+
+    #[test]
+    fn parse_plant_three_column_rows() {
+        let html = r#"<h1 class="entry-title">Алоэ</h1>
+            <img itemprop="url image" data-src="/a.jpg">
+            <table><tbody>
+            <tr><td></td><td>Полив</td><td>умеренный</td></tr>
+            <tr><td>Температура</td><td>18–24 °C</td><td>летом</td></tr>
+            <tr><td colspan="2">Уход</td></tr>
+            </tbody></table>"#;
+        let page = KomnatnieRasteniaAdapter::default()
+            .parse_plant(html, "https://example.com/aloe/")
+            .unwrap();
+        let rows = page
+            .rows
+            .iter()
+            .map(|row| (row.parameter.as_str(), row.value.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [("Полив", "умеренный"), ("Температура", "18–24 °C")]);
+    }
+
+    #[test]
+    fn page_url_with_and_without_trailing_slash() {
+        let adapter = KomnatnieRasteniaAdapter::default();
+        for category in [
+            "https://x.ru/cat/sukkulenty",
+            "https://x.ru/cat/sukkulenty/",
+        ] {
+            assert_eq!(
+                adapter.page_url(category, 2),
+                "https://x.ru/cat/sukkulenty/page/2"
+            );
+        }
+    }
+
+    #[test]
+    fn strip_title_suffix_separators() {
+        assert_eq!(
+            strip_title_suffix("Алоэ — уход в домашних условиях"),
+            "Алоэ"
+        );
+        assert_eq!(strip_title_suffix("Алоэ – уход"), "Алоэ");
+        assert_eq!(strip_title_suffix("Алоэ - уход"), "Алоэ");
+        assert_eq!(strip_title_suffix(" Ванда-Мокара "), "Ванда-Мокара");
+        assert_eq!(strip_title_suffix("Фикус Бенджамина"), "Фикус Бенджамина");
+    }
+
+    #[test]
+    fn category_classes_tried_in_order() {
+        let html = r#"<ul>
+            <li class="menu-item"><a href="/sukkulenty/">Суккуленты</a></li>
+            <li class="menu-item"><a href="/palmy/">Пальмы</a></li>
+        </ul>"#;
+        let adapter = KomnatnieRasteniaAdapter::default();
+        assert!(adapter.category_urls(html).is_empty());
+
+        let adapter =
+            adapter.with_category_classes(vec!["cat-item".to_string(), "menu-item".to_string()]);
+        assert_eq!(adapter.category_urls(html), ["/sukkulenty/", "/palmy/"]);
+    }
+
+    #[test]
+    fn list_cells_keep_items_apart() {
+        let html = r#"<h1 class="entry-title">Алоэ</h1>
+            <img itemprop="url image" data-src="/a.jpg">
+            <table><tbody>
+            <tr><td>Полив</td><td><ul><li>летом обильный</li><li>зимой редкий</li></ul></td></tr>
+            <tr><td>Особенности</td><td><ul><li>ядовит</li><li>цветёт редко</li></ul></td></tr>
+            <tr><td>Грунт</td><td>лёгкий<br>с песком</td></tr>
+            </tbody></table>"#;
+        let page = KomnatnieRasteniaAdapter::default()
+            .parse_plant(html, "https://example.com/aloe/")
+            .unwrap();
+        let values = page
+            .rows
+            .iter()
+            .map(|row| row.value.split_whitespace().collect::<Vec<_>>().join(" "))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            values,
+            [
+                "летом обильный зимой редкий",
+                "ядовит цветёт редко",
+                "лёгкий с песком"
+            ]
+        );
+        let features_value = page.rows[1].features_value.as_deref().unwrap_or_default();
+        assert_eq!(
+            features_value
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" "),
+            "• ядовит • цветёт редко"
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::time::Duration;
+
+#[cfg(feature = "images")]
+use crate::OutputFormat;
+#[cfg(feature = "progress")]
+use crate::ProgressStyle;
+use crate::{
+    adapter::SiteAdapter,
+    database::Database,
+    image_store::{FsImageStore, ImageNaming, ImageStore},
+    ProgressEvent, ScrapeError, Scraper,
+};
+
+/// Checked construction of a `Scraper`. Options left unset keep the
+/// defaults of `Scraper::default`. Every setter mirrors the `with_*`
+/// method of the same name.
+pub struct ScraperBuilder<T: Database> {
+    scraper: Scraper<T>,
+    // First invalid value, reported by `build`
+    error: Option<String>,
+}
+
+// Setters that only forward to `Scraper::with_*`
+macro_rules! forward {
+    ($($(#[$attr:meta])* $name:ident => $with:ident($($arg:ident: $ty:ty),*);)*) => {
+        $(
+            $(#[$attr])*
+            pub fn $name(mut self, $($arg: $ty),*) -> Self {
+                self.scraper = self.scraper.$with($($arg),*);
+                self
+            }
+        )*
+    };
+}
+
+impl<T> ScraperBuilder<T>
+where
+    T: Database,
+{
+    pub fn new() -> Self {
+        Self {
+            scraper: Scraper::default(),
+            error: None,
+        }
+    }
+
+    /// Number of pages fetched at once, must be at least 1.
+    pub fn concurrent_tasks(mut self, concurrent_tasks: usize) -> Self {
+        if concurrent_tasks == 0 {
+            self.invalid("concurrent_tasks must be at least 1".to_string());
+        } else {
+            self.scraper = self.scraper.with_concurrent_tasks(concurrent_tasks);
+        }
+        self
+    }
+
+    /// Store images in `image_dir`, see `Scraper::new`.
+    pub fn image_dir(mut self, image_dir: &str) -> Self {
+        self.scraper = self.scraper.with_image_store(FsImageStore::new(image_dir));
+        self
+    }
+
+    /// Database the scraped plants are saved to.
+    pub fn database(mut self, database: T) -> Self {
+        self.scraper.database = Some(database);
+        self
+    }
+
+    /// Requests per second of the whole scraper, must be positive.
+    /// See `Scraper::with_rate_limit`.
+    pub fn rate_limit(mut self, requests_per_second: f64) -> Self {
+        if requests_per_second.is_finite() && requests_per_second > 0.0 {
+            self.scraper = self.scraper.with_rate_limit(requests_per_second);
+        } else {
+            self.invalid(format!(
+                "rate_limit must be a positive number, got {}",
+                requests_per_second
+            ));
+        }
+        self
+    }
+
+    /// See `Scraper::with_adapter`.
+    pub fn adapter<A: SiteAdapter + 'static>(mut self, adapter: A) -> Self {
+        self.scraper = self.scraper.with_adapter(adapter);
+        self
+    }
+
+    /// See `Scraper::with_image_store`.
+    pub fn image_store<S: ImageStore + 'static>(mut self, image_store: S) -> Self {
+        self.scraper = self.scraper.with_image_store(image_store);
+        self
+    }
+
+    /// See `Scraper::with_progress_callback`.
+    pub fn progress_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(ProgressEvent) + Send + Sync + 'static,
+    {
+        self.scraper = self.scraper.with_progress_callback(callback);
+        self
+    }
+
+    forward! {
+        /// See `Scraper::with_base_url`, must be an absolute URL.
+        base_url => with_base_url(url: &str);
+        /// See `Scraper::with_max_retries`.
+        max_retries => with_max_retries(max_retries: usize);
+        /// See `Scraper::with_timeout`.
+        timeout => with_timeout(timeout: Duration);
+        /// See `Scraper::with_user_agent`.
+        user_agent => with_user_agent(user_agent: &str);
+        /// See `Scraper::with_accept_language`.
+        accept_language => with_accept_language(language: &str);
+        /// See `Scraper::with_proxy`, a malformed URL fails `build`.
+        proxy => with_proxy(proxy_url: &str);
+        /// See `Scraper::with_pool_max_idle_per_host`.
+        pool_max_idle_per_host => with_pool_max_idle_per_host(max_idle: usize);
+        /// See `Scraper::with_pool_idle_timeout`.
+        pool_idle_timeout => with_pool_idle_timeout(timeout: Duration);
+        /// See `Scraper::with_tcp_keepalive`.
+        tcp_keepalive => with_tcp_keepalive(interval: Duration);
+        /// See `Scraper::with_cookies`.
+        #[cfg(feature = "cookies")]
+        cookies => with_cookies(enabled: bool);
+        /// See `Scraper::with_jitter`.
+        jitter => with_jitter(max: Duration);
+        /// See `Scraper::with_jitter_seed`.
+        jitter_seed => with_jitter_seed(max: Duration, seed: u64);
+        /// See `Scraper::with_output_format`.
+        #[cfg(feature = "images")]
+        output_format => with_output_format(format: OutputFormat);
+        /// See `Scraper::with_thumbnail`.
+        #[cfg(feature = "images")]
+        thumbnail => with_thumbnail(max_width: u32, max_height: u32);
+        /// See `Scraper::with_image_concurrency`.
+        image_concurrency => with_image_concurrency(image_tasks: usize);
+        /// See `Scraper::with_strict_attributes`.
+        strict_attributes => with_strict_attributes(strict: bool);
+        /// See `Scraper::with_html_cache`.
+        html_cache => with_html_cache(dir: &str);
+        /// See `Scraper::with_html_dump`.
+        html_dump => with_html_dump(dir: &str);
+        /// See `Scraper::with_html_cache_refresh`.
+        html_cache_refresh => with_html_cache_refresh(refresh: bool);
+        /// See `Scraper::with_client`, overrides the client options set before it.
+        client => with_client(client: reqwest::Client);
+        /// See `Scraper::with_upsert`.
+        upsert => with_upsert(upsert: bool);
+        /// See `Scraper::with_dedup_by_name`.
+        dedup_by_name => with_dedup_by_name(dedup: bool);
+        /// See `Scraper::with_fail_fast`.
+        fail_fast => with_fail_fast(fail_fast: bool);
+        /// See `Scraper::with_batch_size`.
+        batch_size => with_batch_size(batch_size: usize);
+        /// See `Scraper::with_respect_robots`.
+        respect_robots => with_respect_robots(respect_robots: bool);
+        /// See `Scraper::with_images`.
+        images => with_images(enabled: bool);
+        /// See `Scraper::with_image_bytes`.
+        image_bytes => with_image_bytes(enabled: bool);
+        /// See `Scraper::with_debug_attributes`.
+        debug_attributes => with_debug_attributes(enabled: bool);
+        /// See `Scraper::with_image_naming`.
+        image_naming => with_image_naming(naming: ImageNaming);
+        /// See `Scraper::with_attribute_patterns`.
+        attribute_patterns => with_attribute_patterns(regex: regex::Regex);
+        /// See `Scraper::with_progress_style`.
+        #[cfg(feature = "progress")]
+        progress_style => with_progress_style(style: ProgressStyle);
+        /// See `Scraper::with_multi_progress`.
+        #[cfg(feature = "progress")]
+        multi_progress => with_multi_progress(enabled: bool);
+        /// See `Scraper::with_category_filter`.
+        category_filter => with_category_filter(patterns: Vec<String>);
+        /// See `Scraper::with_limit`.
+        limit => with_limit(limit: Option<usize>);
+        /// See `Scraper::with_max_pages_per_category`.
+        max_pages_per_category => with_max_pages_per_category(max_pages: usize);
+        /// See `Scraper::with_refresh_after`.
+        refresh_after => with_refresh_after(age: Duration);
+        /// See `Scraper::with_deadline`.
+        deadline => with_deadline(deadline: Duration);
+        /// See `Scraper::with_checkpoint`.
+        checkpoint => with_checkpoint(path: &str);
+    }
+
+    /// Validate the options and create the scraper. Unlike the `with_*`
+    /// methods, invalid values are errors instead of being corrected or
+    /// failing every request later.
+    pub fn build(self) -> Result<Scraper<T>, ScrapeError> {
+        if let Some(err) = self.error {
+            return Err(ScrapeError::InvalidConfig(err));
+        }
+        let scraper = self.scraper;
+        if let Err(err) = url::Url::parse(&scraper.base_url) {
+            return Err(ScrapeError::InvalidConfig(format!(
+                "Invalid base URL {}: {}",
+                scraper.base_url, err
+            )));
+        }
+        if let Some(err) = &scraper.rate_limit_error {
+            return Err(ScrapeError::InvalidConfig(err.clone()));
+        }
+        if let Some(err) = &scraper.client_error {
+            return Err(ScrapeError::InvalidConfig(format!(
+                "Can't build HTTP client: {}",
+                err
+            )));
+        }
+        Ok(scraper)
+    }
+
+    fn invalid(&mut self, err: String) {
+        self.error.get_or_insert(err);
+    }
+}
+
+impl<T> Default for ScraperBuilder<T>
+where
+    T: Database,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,58 @@
+use encoding_rs::{Encoding, UTF_8};
+
+lazy_static::lazy_static! {
+    static ref META_CHARSET_RE: regex::bytes::Regex =
+        regex::bytes::Regex::new(r#"(?i)<meta[^>]+charset\s*=\s*["']?([\w-]+)"#).unwrap();
+}
+
+// Decode a page by the charset of its Content-Type, then of its
+// `<meta>` tag, defaulting to UTF-8. Old sites often serve windows-1251.
+pub(crate) fn decode_html(bytes: &[u8], content_type: Option<&str>) -> String {
+    let from_header = content_type.and_then(|value| {
+        value
+            .split(';')
+            .filter_map(|param| param.trim().split_once('='))
+            .find(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+            .and_then(|(_, label)| Encoding::for_label(label.trim_matches('"').as_bytes()))
+    });
+    // The tag must be in the first 1024 bytes, as browsers require
+    let from_meta = || {
+        let head = &bytes[..bytes.len().min(1024)];
+        let label = META_CHARSET_RE.captures(head)?.get(1)?;
+        Encoding::for_label(label.as_bytes())
+    };
+    let encoding = from_header.or_else(from_meta).unwrap_or(UTF_8);
+    let (text, _, _) = encoding.decode(bytes);
+    text.into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows_1251(text: &str) -> Vec<u8> {
+        let (bytes, _, _) = encoding_rs::WINDOWS_1251.encode(text);
+        bytes.into_owned()
+    }
+
+    #[test]
+    fn decodes_windows_1251() {
+        let bytes = windows_1251("<p>Полив умеренный</p>");
+        let text = decode_html(&bytes, Some("text/html; charset=\"windows-1251\""));
+        assert_eq!(text, "<p>Полив умеренный</p>");
+
+        let html = r#"<html><head><meta charset="windows-1251"></head><p>Фикус</p></html>"#;
+        let text = decode_html(&windows_1251(html), Some("text/html"));
+        assert_eq!(text, html);
+    }
+
+    #[test]
+    fn header_wins_over_meta_and_utf8_is_default() {
+        let html = r#"<meta http-equiv="Content-Type" content="text/html; charset=windows-1251"><p>Алоэ</p>"#;
+        assert_eq!(
+            decode_html(html.as_bytes(), Some("text/html; charset=UTF-8")),
+            html
+        );
+        assert_eq!(decode_html("<p>Алоэ</p>".as_bytes(), None), "<p>Алоэ</p>");
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+
+// Save after this many new URLs, so a crash loses little work without
+// rewriting the file for every plant
+const SAVE_EVERY: usize = 50;
+
+// Plant URLs already parsed by an interrupted run, one per line
+pub(crate) struct Checkpoint {
+    path: PathBuf,
+    done: HashSet<String>,
+    unsaved: usize,
+}
+
+impl Checkpoint {
+    // Missing file is an empty checkpoint
+    pub(crate) async fn load(path: &Path) -> Result<Self> {
+        let done = match tokio::fs::read_to_string(path).await {
+            Ok(content) => content
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(|line| line.to_string())
+                .collect(),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => HashSet::new(),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Can't read checkpoint {}", path.display()))
+            }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            done,
+            unsaved: 0,
+        })
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.done.len()
+    }
+
+    pub(crate) fn contains(&self, url: &str) -> bool {
+        self.done.contains(url)
+    }
+
+    pub(crate) async fn add(&mut self, url: &str) -> Result<()> {
+        if self.done.insert(url.to_string()) {
+            self.unsaved += 1;
+        }
+        if self.unsaved >= SAVE_EVERY {
+            self.save().await?;
+        }
+        Ok(())
+    }
+
+    // Write to a temporary file and rename it into place, so a crash
+    // never leaves a truncated checkpoint
+    pub(crate) async fn save(&mut self) -> Result<()> {
+        let mut urls = self.done.iter().map(|url| url.as_str()).collect::<Vec<_>>();
+        urls.sort_unstable();
+        let mut content = urls.join("\n");
+        content.push('\n');
+        let mut temp_path = self.path.clone().into_os_string();
+        temp_path.push(".tmp");
+        let temp_path = PathBuf::from(temp_path);
+        tokio::fs::write(&temp_path, content)
+            .await
+            .with_context(|| format!("Can't write checkpoint {}", temp_path.display()))?;
+        tokio::fs::rename(&temp_path, &self.path)
+            .await
+            .with_context(|| format!("Can't move checkpoint to {}", self.path.display()))?;
+        self.unsaved = 0;
+        Ok(())
+    }
+
+    // The run is complete, the next one starts from scratch
+    pub(crate) async fn remove(self) -> Result<()> {
+        match tokio::fs::remove_file(&self.path).await {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                Err(err).with_context(|| format!("Can't remove checkpoint {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
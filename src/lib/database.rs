@@ -1,83 +1,68 @@
-use crate::{Houseplant, OptArg};
-use anyhow::Result;
+#[cfg(any(test, feature = "testing"))]
+mod memory;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+
+use crate::Houseplant;
+use anyhow::{anyhow, Result};
 use async_trait::async_trait;
-use sqlx::sqlite::SqlitePoolOptions;
+use std::time::SystemTime;
+
+#[cfg(any(test, feature = "testing"))]
+pub use memory::InMemoryDatabase;
+#[cfg(feature = "sqlite")]
+pub use sqlite::Sqlite;
 
 #[async_trait]
-pub trait Database {
+pub trait Database: Send + Sync {
     async fn insert(&self, plant: &Houseplant) -> Result<()>;
-}
 
-pub struct Sqlite {
-    pool: sqlx::Pool<sqlx::Sqlite>,
-}
+    async fn exists(&self, _plant_url: &str) -> Result<bool> {
+        Ok(false)
+    }
 
-impl Sqlite {
-    pub async fn new(database_url: &str) -> Result<Self> {
-        let pool = SqlitePoolOptions::new().connect(database_url).await?;
-        let mut conn = pool.acquire().await?;
-        sqlx::query!(
-            r#"
-            CREATE TABLE IF NOT EXISTS plants
-            (
-                name TEXT NOT NULL,
-                image TEXT,
-                temperature TEXT,
-                humidity TEXT,
-                illumination TEXT,
-                watering TEXT,
-                soil TEXT,
-                fertilizer TEXT,
-                transplant TEXT,
-                propagation TEXT,
-                features TEXT
-            );
-            "#
-        )
-        .execute(&mut conn)
-        .await?;
+    // Time the stored plant page was scraped, `None` if unknown
+    async fn scraped_at(&self, _plant_url: &str) -> Result<Option<SystemTime>> {
+        Ok(None)
+    }
 
-        Ok(Self { pool })
+    async fn insert_batch(&self, plants: &[Houseplant]) -> Result<()> {
+        for plant in plants {
+            self.insert(plant).await?;
+        }
+        Ok(())
     }
-}
 
-#[async_trait]
-impl Database for Sqlite {
-    async fn insert(&self, plant: &Houseplant) -> Result<()> {
-        let attrs = &plant.attributes;
-        let temperature = attrs.temperature.get_value();
-        let humidity = attrs.humidity.get_value();
-        let illumination = attrs.illumination.get_value();
-        let watering = attrs.watering.get_value();
-        let soil = attrs.soil.get_value();
-        let fertilizer = attrs.fertilizer.get_value();
-        let transplant = attrs.transplant.get_value();
-        let propagation = attrs.propagation.get_value();
-        let features = attrs.features.get_value();
+    // Insert, replacing the plant previously stored for the same page
+    async fn upsert(&self, plant: &Houseplant) -> Result<()> {
+        if self.exists(&plant.source_url).await? {
+            self.delete(&plant.source_url).await?;
+        }
+        self.insert(plant).await
+    }
 
-        let mut conn = self.pool.acquire().await?;
-        let _res = sqlx::query!(
-            r#"
-            INSERT INTO plants (
-                name, image, temperature, humidity, illumination, watering,
-                soil, fertilizer, transplant, propagation, features
-            )
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
-            "#,
-            plant.name,
-            plant.image,
-            temperature,
-            humidity,
-            illumination,
-            watering,
-            soil,
-            fertilizer,
-            transplant,
-            propagation,
-            features
-        )
-        .execute(&mut conn)
-        .await?;
+    async fn upsert_batch(&self, plants: &[Houseplant]) -> Result<()> {
+        for plant in plants {
+            self.upsert(plant).await?;
+        }
         Ok(())
     }
+
+    async fn delete(&self, _plant_url: &str) -> Result<()> {
+        Err(anyhow!("delete is not supported by this database"))
+    }
+
+    // Write out anything the database buffers itself, called once a
+    // scrape ends, also when it's cancelled
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_all(&self) -> Result<Vec<Houseplant>> {
+        Err(anyhow!("get_all is not supported by this database"))
+    }
+
+    async fn get_by_name(&self, _name: &str) -> Result<Option<Houseplant>> {
+        Err(anyhow!("get_by_name is not supported by this database"))
+    }
 }
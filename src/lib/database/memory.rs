@@ -0,0 +1,67 @@
+use super::Database;
+use crate::Houseplant;
+use anyhow::Result;
+use async_trait::async_trait;
+use std::{sync::Mutex, time::SystemTime};
+
+// Keeps plants in memory, handy to check what the scraper stored
+#[derive(Default)]
+pub struct InMemoryDatabase {
+    plants: Mutex<Vec<(Houseplant, SystemTime)>>,
+}
+
+impl InMemoryDatabase {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl Database for InMemoryDatabase {
+    async fn insert(&self, plant: &Houseplant) -> Result<()> {
+        let mut plants = self.plants.lock().unwrap();
+        plants.push((plant.clone(), SystemTime::now()));
+        Ok(())
+    }
+
+    async fn upsert(&self, plant: &Houseplant) -> Result<()> {
+        let mut plants = self.plants.lock().unwrap();
+        plants.retain(|(stored, _)| stored.source_url != plant.source_url);
+        plants.push((plant.clone(), SystemTime::now()));
+        Ok(())
+    }
+
+    async fn delete(&self, plant_url: &str) -> Result<()> {
+        let mut plants = self.plants.lock().unwrap();
+        plants.retain(|(plant, _)| plant.source_url != plant_url);
+        Ok(())
+    }
+
+    async fn exists(&self, plant_url: &str) -> Result<bool> {
+        let plants = self.plants.lock().unwrap();
+        Ok(plants
+            .iter()
+            .any(|(plant, _)| plant.source_url == plant_url))
+    }
+
+    async fn scraped_at(&self, plant_url: &str) -> Result<Option<SystemTime>> {
+        let plants = self.plants.lock().unwrap();
+        Ok(plants
+            .iter()
+            .find(|(plant, _)| plant.source_url == plant_url)
+            .map(|(_, scraped_at)| *scraped_at))
+    }
+
+    async fn get_all(&self) -> Result<Vec<Houseplant>> {
+        let plants = self.plants.lock().unwrap();
+        Ok(plants.iter().map(|(plant, _)| plant.clone()).collect())
+    }
+
+    async fn get_by_name(&self, name: &str) -> Result<Option<Houseplant>> {
+        let plants = self.plants.lock().unwrap();
+        Ok(plants
+            .iter()
+            .find(|(plant, _)| plant.name == name)
+            .map(|(plant, _)| plant.clone()))
+    }
+}
@@ -0,0 +1,379 @@
+use super::Database;
+use crate::{Attribute, Attributes, Houseplant, OptArg};
+use anyhow::Result;
+use async_trait::async_trait;
+use log::info;
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Row,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Columns added after the first schema, in the order they were added
+const ADDED_COLUMNS: [(&str, &str); 5] = [
+    ("source_url", "TEXT"),
+    ("image_filename", "TEXT"),
+    ("image_url", "TEXT"),
+    ("scraped_at", "INTEGER"),
+    ("thumbnail", "TEXT"),
+];
+
+// Clones share the connection pool
+#[derive(Clone)]
+pub struct Sqlite {
+    pool: sqlx::Pool<sqlx::Sqlite>,
+}
+
+struct PlantRow {
+    name: String,
+    source_url: Option<String>,
+    image_filename: Option<String>,
+    thumbnail: Option<String>,
+    image_url: Option<String>,
+    temperature: Option<String>,
+    humidity: Option<String>,
+    illumination: Option<String>,
+    watering: Option<String>,
+    soil: Option<String>,
+    fertilizer: Option<String>,
+    transplant: Option<String>,
+    propagation: Option<String>,
+    features: Option<String>,
+}
+
+impl From<PlantRow> for Houseplant {
+    fn from(row: PlantRow) -> Self {
+        // Only values are stored, so use the usual table labels as parameters
+        let attr = |parameter: &str, value: Option<String>| {
+            value.map(|value| Attribute {
+                parameter: parameter.to_string(),
+                value,
+            })
+        };
+        Houseplant {
+            name: row.name,
+            source_url: row.source_url.unwrap_or_default(),
+            image_filename: row.image_filename,
+            thumbnail: row.thumbnail,
+            html_dump: None,
+            image_bytes: None,
+            attribute_sources: None,
+            image_url: row.image_url.unwrap_or_default(),
+            attributes: Attributes {
+                temperature: attr("Температура", row.temperature),
+                humidity: attr("Влажность", row.humidity),
+                illumination: attr("Освещение", row.illumination),
+                watering: attr("Полив", row.watering),
+                soil: attr("Грунт", row.soil),
+                fertilizer: attr("Подкормка", row.fertilizer),
+                transplant: attr("Пересадка", row.transplant),
+                propagation: attr("Размножение", row.propagation),
+                features: attr("Особенности", row.features),
+            },
+        }
+    }
+}
+
+impl Sqlite {
+    pub async fn new(database_url: &str) -> Result<Self> {
+        let pool = SqlitePoolOptions::new().connect(database_url).await?;
+        let db = Self { pool };
+        db.migrate().await?;
+        Ok(db)
+    }
+
+    // Open database file, creating it if missing
+    pub async fn open(path: &str) -> Result<Self> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new().connect_with(options).await?;
+        let db = Self { pool };
+        db.migrate().await?;
+        Ok(db)
+    }
+
+    async fn migrate(&self) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query!(
+            r#"
+            CREATE TABLE IF NOT EXISTS plants
+            (
+                name TEXT NOT NULL,
+                source_url TEXT,
+                image_filename TEXT,
+                thumbnail TEXT,
+                image_url TEXT,
+                temperature TEXT,
+                humidity TEXT,
+                illumination TEXT,
+                watering TEXT,
+                soil TEXT,
+                fertilizer TEXT,
+                transplant TEXT,
+                propagation TEXT,
+                features TEXT,
+                scraped_at INTEGER
+            );
+            "#
+        )
+        .execute(&mut conn)
+        .await?;
+
+        // Tables created by earlier versions lack the newer columns
+        let columns = sqlx::query("PRAGMA table_info(plants)")
+            .fetch_all(&mut conn)
+            .await?
+            .iter()
+            .map(|row| row.try_get::<String, _>("name"))
+            .collect::<Result<Vec<_>, _>>()?;
+        for (column, kind) in ADDED_COLUMNS {
+            if !columns.iter().any(|name| name == column) {
+                info!("Добавляем в базу столбец {}", column);
+                sqlx::query(&format!(
+                    "ALTER TABLE plants ADD COLUMN {} {}",
+                    column, kind
+                ))
+                .execute(&mut conn)
+                .await?;
+            }
+        }
+        // The first schema kept the image file name in `image`
+        let has = |column: &str| columns.iter().any(|name| name == column);
+        if has("image") && !has("image_filename") {
+            sqlx::query("UPDATE plants SET image_filename = image")
+                .execute(&mut conn)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Database for Sqlite {
+    async fn insert(&self, plant: &Houseplant) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        insert_plant(&mut conn, plant).await
+    }
+
+    async fn exists(&self, plant_url: &str) -> Result<bool> {
+        let mut conn = self.pool.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+            SELECT COUNT(*) AS count FROM plants WHERE source_url = ?
+            "#,
+            plant_url
+        )
+        .fetch_one(&mut conn)
+        .await?;
+        Ok(row.count > 0)
+    }
+
+    async fn scraped_at(&self, plant_url: &str) -> Result<Option<SystemTime>> {
+        let mut conn = self.pool.acquire().await?;
+        let row = sqlx::query!(
+            r#"
+            SELECT scraped_at FROM plants WHERE source_url = ? LIMIT 1
+            "#,
+            plant_url
+        )
+        .fetch_optional(&mut conn)
+        .await?;
+        Ok(row
+            .and_then(|row| row.scraped_at)
+            .map(|secs| UNIX_EPOCH + Duration::from_secs(secs as u64)))
+    }
+
+    async fn get_all(&self) -> Result<Vec<Houseplant>> {
+        let mut conn = self.pool.acquire().await?;
+        let rows = sqlx::query_as!(
+            PlantRow,
+            r#"
+            SELECT
+                name, source_url, image_filename, thumbnail, image_url,
+                temperature, humidity, illumination, watering, soil, fertilizer,
+                transplant, propagation, features
+            FROM plants
+            "#
+        )
+        .fetch_all(&mut conn)
+        .await?;
+        Ok(rows.into_iter().map(Houseplant::from).collect())
+    }
+
+    async fn get_by_name(&self, name: &str) -> Result<Option<Houseplant>> {
+        let mut conn = self.pool.acquire().await?;
+        let row = sqlx::query_as!(
+            PlantRow,
+            r#"
+            SELECT
+                name, source_url, image_filename, thumbnail, image_url,
+                temperature, humidity, illumination, watering, soil, fertilizer,
+                transplant, propagation, features
+            FROM plants
+            WHERE name = ?
+            "#,
+            name
+        )
+        .fetch_optional(&mut conn)
+        .await?;
+        Ok(row.map(Houseplant::from))
+    }
+
+    async fn insert_batch(&self, plants: &[Houseplant]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for plant in plants {
+            insert_plant(&mut tx, plant).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert(&self, plant: &Houseplant) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        delete_plant(&mut tx, &plant.source_url).await?;
+        insert_plant(&mut tx, plant).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn upsert_batch(&self, plants: &[Houseplant]) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        for plant in plants {
+            delete_plant(&mut tx, &plant.source_url).await?;
+            insert_plant(&mut tx, plant).await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn delete(&self, plant_url: &str) -> Result<()> {
+        let mut conn = self.pool.acquire().await?;
+        delete_plant(&mut conn, plant_url).await
+    }
+}
+
+async fn delete_plant(conn: &mut sqlx::SqliteConnection, plant_url: &str) -> Result<()> {
+    sqlx::query!(
+        r#"
+        DELETE FROM plants WHERE source_url = ?
+        "#,
+        plant_url
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+async fn insert_plant(conn: &mut sqlx::SqliteConnection, plant: &Houseplant) -> Result<()> {
+    let attrs = &plant.attributes;
+    let temperature = attrs.temperature.get_value();
+    let humidity = attrs.humidity.get_value();
+    let illumination = attrs.illumination.get_value();
+    let watering = attrs.watering.get_value();
+    let soil = attrs.soil.get_value();
+    let fertilizer = attrs.fertilizer.get_value();
+    let transplant = attrs.transplant.get_value();
+    let propagation = attrs.propagation.get_value();
+    let features = attrs.features.get_value();
+    let scraped_at = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64;
+
+    let _res = sqlx::query!(
+        r#"
+        INSERT INTO plants (
+            name, source_url, image_filename, thumbnail, image_url, temperature,
+            humidity, illumination, watering, soil, fertilizer, transplant,
+            propagation, features, scraped_at
+        )
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        "#,
+        plant.name,
+        plant.source_url,
+        plant.image_filename,
+        plant.thumbnail,
+        plant.image_url,
+        temperature,
+        humidity,
+        illumination,
+        watering,
+        soil,
+        fertilizer,
+        transplant,
+        propagation,
+        features,
+        scraped_at
+    )
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_plant;
+
+    // sqlx runs SQLite calls with `block_in_place`
+    #[tokio::test(flavor = "multi_thread")]
+    async fn inserts_and_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plants.db");
+        let db = Sqlite::open(path.to_str().unwrap()).await.unwrap();
+        let ficus = sample_plant("Фикус", "https://example.com/ficus/");
+        let aloe = Houseplant {
+            thumbnail: None,
+            attributes: Attributes::default(),
+            ..sample_plant("Алоэ", "https://example.com/aloe/")
+        };
+        db.insert(&ficus).await.unwrap();
+        db.insert(&aloe).await.unwrap();
+
+        assert_eq!(db.get_all().await.unwrap(), vec![ficus.clone(), aloe]);
+        assert_eq!(db.get_by_name("Фикус").await.unwrap(), Some(ficus));
+        assert_eq!(db.get_by_name("Кактус").await.unwrap(), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn migrates_baseline_schema() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("plants.db");
+        let path = path.to_str().unwrap();
+        // Schema and row as written by the first release
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .connect_with(options)
+            .await
+            .unwrap();
+        sqlx::query(
+            "CREATE TABLE plants (name TEXT NOT NULL, image TEXT, temperature TEXT, \
+             humidity TEXT, illumination TEXT, watering TEXT, soil TEXT, fertilizer TEXT, \
+             transplant TEXT, propagation TEXT, features TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO plants (name, image, watering) VALUES ('Алоэ', 'old.jpg', 'редкий')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        pool.close().await;
+
+        let db = Sqlite::open(path).await.unwrap();
+        let plant = sample_plant("Фикус", "https://example.com/ficus/");
+        db.insert(&plant).await.unwrap();
+
+        let old = db.get_by_name("Алоэ").await.unwrap().unwrap();
+        assert_eq!(old.image_filename.as_deref(), Some("old.jpg"));
+        assert_eq!(old.attributes.watering.get_value(), Some("редкий"));
+        assert!(db.exists(&plant.source_url).await.unwrap());
+        assert!(db.scraped_at(&plant.source_url).await.unwrap().is_some());
+        assert_eq!(db.get_by_name("Фикус").await.unwrap(), Some(plant));
+        // Migration runs once
+        drop(db);
+        Sqlite::open(path).await.unwrap();
+    }
+}
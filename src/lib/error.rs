@@ -0,0 +1,46 @@
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ScrapeError {
+    #[error("HTTP request failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("Can't parse page: {0}")]
+    Parse(String),
+    #[error("Can't download image: {0:#}")]
+    ImageDownload(anyhow::Error),
+    #[error("Database error: {0:#}")]
+    Database(anyhow::Error),
+    #[error("Can't find {what}")]
+    MissingElement { what: String },
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+// Internal helpers return `anyhow::Error`, recover the typed error from it
+impl From<anyhow::Error> for ScrapeError {
+    fn from(err: anyhow::Error) -> Self {
+        let err = match err.downcast::<ScrapeError>() {
+            Ok(err) => return err,
+            Err(err) => err,
+        };
+        let err = match err.downcast::<reqwest::Error>() {
+            Ok(err) => return ScrapeError::Http(err),
+            Err(err) => err,
+        };
+        match err.downcast::<std::io::Error>() {
+            Ok(err) => ScrapeError::Io(err),
+            Err(err) => ScrapeError::Other(err),
+        }
+    }
+}
+
+pub(crate) fn missing(what: &str) -> anyhow::Error {
+    ScrapeError::MissingElement {
+        what: what.to_string(),
+    }
+    .into()
+}
@@ -0,0 +1,103 @@
+use std::io::Write;
+
+use anyhow::{Context, Result};
+
+use crate::Houseplant;
+#[cfg(feature = "csv")]
+use crate::{Attribute, OptArg};
+
+// Save plants as pretty JSON file
+#[cfg(feature = "serde")]
+pub fn write_json(plants: &[Houseplant], path: &str) -> Result<()> {
+    let file =
+        std::fs::File::create(path).with_context(|| format!("Can't create JSON file {}", path))?;
+    write_json_to_writer(plants, std::io::BufWriter::new(file))
+}
+
+#[cfg(feature = "serde")]
+pub fn write_json_to_writer<W: Write>(plants: &[Houseplant], mut writer: W) -> Result<()> {
+    serde_json::to_writer_pretty(&mut writer, plants).context("Can't serialize plants to JSON")?;
+    writer.flush()?;
+    Ok(())
+}
+
+// Save plants as CSV file, one row per plant
+#[cfg(feature = "csv")]
+pub fn write_csv(plants: &[Houseplant], path: &str) -> Result<()> {
+    let file =
+        std::fs::File::create(path).with_context(|| format!("Can't create CSV file {}", path))?;
+    write_csv_to_writer(plants, std::io::BufWriter::new(file))
+}
+
+#[cfg(feature = "csv")]
+pub fn write_csv_to_writer<W: Write>(plants: &[Houseplant], writer: W) -> Result<()> {
+    // Values with commas, quotes or newlines are quoted by the writer
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record([
+        "name",
+        "image_filename",
+        "thumbnail",
+        "image_url",
+        "source_url",
+        "temperature",
+        "humidity",
+        "illumination",
+        "watering",
+        "soil",
+        "fertilizer",
+        "transplant",
+        "propagation",
+        "features",
+    ])?;
+    let value = |attr: &Option<Attribute>| attr.get_value().unwrap_or_default().to_string();
+    for plant in plants {
+        let attrs = &plant.attributes;
+        writer.write_record([
+            plant.name.clone(),
+            plant.image_filename.clone().unwrap_or_default(),
+            plant.thumbnail.clone().unwrap_or_default(),
+            plant.image_url.clone(),
+            plant.source_url.clone(),
+            value(&attrs.temperature),
+            value(&attrs.humidity),
+            value(&attrs.illumination),
+            value(&attrs.watering),
+            value(&attrs.soil),
+            value(&attrs.fertilizer),
+            value(&attrs.transplant),
+            value(&attrs.propagation),
+            value(&attrs.features),
+        ])?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(all(test, feature = "csv"))]
+mod tests {
+    use super::*;
+    use crate::test_util::sample_plant;
+
+    #[test]
+    fn csv_round_trip() {
+        let mut plant = sample_plant("Фикус, \"каучуконосный\"\nпёстрый", "https://x/ficus/");
+        plant.attributes.features = None;
+        let mut output = Vec::new();
+        write_csv_to_writer(&[plant.clone()], &mut output).unwrap();
+
+        let mut reader = csv::Reader::from_reader(output.as_slice());
+        let headers = reader.headers().unwrap().clone();
+        assert_eq!(headers.len(), 14);
+        assert_eq!(&headers[0], "name");
+        assert_eq!(&headers[13], "features");
+        let rows = reader.records().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(rows.len(), 1);
+        let row = &rows[0];
+        assert_eq!(&row[0], plant.name);
+        assert_eq!(&row[1], "abc.jpg");
+        assert_eq!(&row[4], "https://x/ficus/");
+        assert_eq!(&row[5], "18–24 °C");
+        assert_eq!(&row[8], "летом обильный, зимой умеренный");
+        assert_eq!(&row[13], "");
+    }
+}
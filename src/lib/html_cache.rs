@@ -0,0 +1,62 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use log::warn;
+use sha2::{Digest, Sha256};
+
+// Fetched pages on disk, one file per URL named by the URL hash.
+// Clones write to the same directory, so they share the temp file counter
+#[derive(Clone)]
+pub(crate) struct HtmlCache {
+    dir: PathBuf,
+    write_counter: Arc<AtomicUsize>,
+}
+
+impl HtmlCache {
+    pub(crate) fn new(dir: &str) -> Self {
+        Self {
+            dir: PathBuf::from(dir),
+            write_counter: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    fn path(&self, url: &str) -> PathBuf {
+        page_path(&self.dir, url)
+    }
+
+    pub(crate) async fn get(&self, url: &str) -> Option<String> {
+        tokio::fs::read_to_string(self.path(url)).await.ok()
+    }
+
+    pub(crate) async fn put(&self, url: &str, html: &str) {
+        if let Err(err) = self.write(url, html).await {
+            warn!("Не удалось сохранить страницу {} в кэш: {}", url, err);
+        }
+    }
+
+    async fn write(&self, url: &str, html: &str) -> std::io::Result<()> {
+        let path = self.path(url);
+        let write_id = self.write_counter.fetch_add(1, Ordering::Relaxed);
+        let temp_path = path.with_extension(format!("{}.tmp", write_id));
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(&temp_path, html).await?;
+        tokio::fs::rename(&temp_path, &path).await
+    }
+}
+
+fn page_path(dir: &Path, url: &str) -> PathBuf {
+    dir.join(format!("{:x}.html", Sha256::digest(url.as_bytes())))
+}
+
+// Keep the page a plant was parsed from, returns the written file path
+pub(crate) async fn dump_page(dir: &Path, url: &str, html: &str) -> std::io::Result<String> {
+    let path = page_path(dir, url);
+    tokio::fs::create_dir_all(dir).await?;
+    tokio::fs::write(&path, html).await?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,60 @@
+use anyhow::Result;
+use image::{GenericImageView, ImageFormat};
+
+/// Format images are re-encoded to, see `Scraper::with_output_format`.
+/// Only formats with an encoder in the enabled `image` codecs are listed,
+/// WebP can be decoded but not encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Jpeg,
+    Png,
+}
+
+impl OutputFormat {
+    // Format of a stored image by its extension, PNG for the rest
+    pub(crate) fn from_extension(extension: &str) -> Self {
+        match extension.to_lowercase().as_str() {
+            "jpg" | "jpeg" => OutputFormat::Jpeg,
+            _ => OutputFormat::Png,
+        }
+    }
+
+    pub(crate) fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+        }
+    }
+
+    fn image_format(self) -> ImageFormat {
+        match self {
+            OutputFormat::Jpeg => ImageFormat::Jpeg,
+            OutputFormat::Png => ImageFormat::Png,
+        }
+    }
+}
+
+// Decode image and encode it again in `format`
+pub(crate) fn reencode(bytes: &[u8], format: OutputFormat) -> Result<Vec<u8>> {
+    let image = image::load_from_memory(bytes)?;
+    let mut output = Vec::new();
+    image.write_to(&mut output, format.image_format())?;
+    Ok(output)
+}
+
+// Scale image down to fit the bounds, keeping its aspect ratio
+pub(crate) fn thumbnail(
+    bytes: &[u8],
+    max_width: u32,
+    max_height: u32,
+    format: OutputFormat,
+) -> Result<Vec<u8>> {
+    let mut image = image::load_from_memory(bytes)?;
+    // Small images are kept as they are, never scaled up
+    if image.width() > max_width || image.height() > max_height {
+        image = image.thumbnail(max_width, max_height);
+    }
+    let mut output = Vec::new();
+    image.write_to(&mut output, format.image_format())?;
+    Ok(output)
+}
@@ -0,0 +1,213 @@
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use sha2::{Digest, Sha256};
+
+// Stored image with HTTP validators of the response it came from
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachedImage {
+    pub name: String,
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+// How downloaded images are named in the store
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ImageNaming {
+    // SHA-256 of the image, the same image is stored once
+    #[default]
+    ContentHash,
+    // Milliseconds since the Unix epoch at download
+    Timestamp,
+    // Plant name, e.g. `фикус-бенджамина.jpg`
+    PlantSlug,
+}
+
+// Lowercase letters and digits of `name` separated by dashes
+pub(crate) fn slugify(name: &str) -> String {
+    let slug = name
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "image".to_string()
+    } else {
+        slug
+    }
+}
+
+// Downloaded image kept in memory, see `Scraper::with_image_bytes`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageBytes {
+    // Name the image would be stored with
+    pub name: String,
+    pub content_type: &'static str,
+    pub bytes: Vec<u8>,
+}
+
+#[async_trait]
+pub trait ImageStore: Send + Sync {
+    // Save image and return identifier to keep in `Houseplant.image_filename`
+    async fn store(&self, bytes: &[u8], suggested_name: &str) -> Result<String>;
+
+    // Check that stored image is complete, stores that can't tell accept all
+    async fn verify(&self, _name: &str) -> Result<bool> {
+        Ok(true)
+    }
+
+    // Read stored image back, `None` if missing or the store can't read
+    async fn load(&self, _name: &str) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    // Image previously stored for `image_url`, to revalidate instead of
+    // downloading it again
+    async fn cached(&self, _image_url: &str) -> Result<Option<CachedImage>> {
+        Ok(None)
+    }
+
+    async fn remember(&self, _image_url: &str, _image: &CachedImage) -> Result<()> {
+        Ok(())
+    }
+}
+
+// Image format by leading magic bytes, as file extension
+pub(crate) fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else {
+        None
+    }
+}
+
+// Known format with its end marker in place, so the file isn't truncated
+fn image_complete(bytes: &[u8]) -> bool {
+    match sniff_image(bytes) {
+        Some("jpg") => bytes.ends_with(&[0xFF, 0xD9]),
+        Some("png") => bytes.ends_with(b"IEND\xAE\x42\x60\x82"),
+        Some("webp") => {
+            let size = u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]);
+            bytes.len() >= size as usize + 8
+        }
+        _ => false,
+    }
+}
+
+pub struct FsImageStore {
+    image_dir: PathBuf,
+    download_counter: AtomicUsize,
+    dir_created: AtomicBool,
+}
+
+impl FsImageStore {
+    pub fn new(image_dir: &str) -> Self {
+        Self {
+            image_dir: PathBuf::from(image_dir),
+            download_counter: AtomicUsize::new(0),
+            dir_created: AtomicBool::new(false),
+        }
+    }
+
+    // Create image dir before the first write only
+    async fn ensure_dir(&self) -> Result<()> {
+        if !self.dir_created.load(Ordering::Acquire) {
+            tokio::fs::create_dir_all(&self.image_dir)
+                .await
+                .with_context(|| format!("Can't create image dir {}", self.image_dir.display()))?;
+            self.dir_created.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    // Sidecar file with validators, named by the image url hash
+    fn validators_path(&self, image_url: &str) -> PathBuf {
+        self.image_dir
+            .join(".http")
+            .join(format!("{:x}", Sha256::digest(image_url.as_bytes())))
+    }
+}
+
+// File IO goes through `tokio::fs`, so concurrent downloads don't block
+// the runtime worker threads
+#[async_trait]
+impl ImageStore for FsImageStore {
+    async fn store(&self, bytes: &[u8], suggested_name: &str) -> Result<String> {
+        let image_dir = &self.image_dir;
+        let image_path = image_dir.join(suggested_name);
+        if tokio::fs::metadata(&image_path).await.is_ok() {
+            return Ok(suggested_name.to_string());
+        }
+        self.ensure_dir().await?;
+        // Write to a unique temporary file and rename it into place, so
+        // concurrent downloads never clobber or expose a half-written file
+        let download_id = self.download_counter.fetch_add(1, Ordering::Relaxed);
+        let temp_path = image_dir.join(format!("{}.{}.tmp", suggested_name, download_id));
+        tokio::fs::write(&temp_path, bytes)
+            .await
+            .with_context(|| format!("Can't write image file {}", temp_path.display()))?;
+        tokio::fs::rename(&temp_path, &image_path)
+            .await
+            .with_context(|| format!("Can't move image file to {}", image_path.display()))?;
+        Ok(suggested_name.to_string())
+    }
+
+    async fn cached(&self, image_url: &str) -> Result<Option<CachedImage>> {
+        let content = match tokio::fs::read_to_string(self.validators_path(image_url)).await {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Can't read image dir {}", self.image_dir.display()))
+            }
+        };
+        // Lines: file name, ETag, Last-Modified, the last two may be empty
+        let mut lines = content.lines().map(|line| line.to_string());
+        let optional = |line: Option<String>| line.filter(|line| !line.is_empty());
+        Ok(lines.next().map(|name| CachedImage {
+            name,
+            etag: optional(lines.next()),
+            last_modified: optional(lines.next()),
+        }))
+    }
+
+    async fn remember(&self, image_url: &str, image: &CachedImage) -> Result<()> {
+        let path = self.validators_path(image_url);
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await?;
+        }
+        let content = format!(
+            "{}\n{}\n{}\n",
+            image.name,
+            image.etag.as_deref().unwrap_or_default(),
+            image.last_modified.as_deref().unwrap_or_default()
+        );
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    async fn load(&self, name: &str) -> Result<Option<Vec<u8>>> {
+        match tokio::fs::read(self.image_dir.join(name)).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    async fn verify(&self, name: &str) -> Result<bool> {
+        match tokio::fs::read(self.image_dir.join(name)).await {
+            Ok(bytes) => Ok(image_complete(&bytes)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+#[cfg(feature = "progress")]
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProgressEvent {
+    CategoryDone { current: usize, total: usize },
+    PlantDone { current: usize, total: usize },
+    // Category list pages in stage 2, `total` grows as categories are opened
+    PageDone { current: usize, total: usize },
+}
+
+pub type ProgressCallback = Box<dyn Fn(ProgressEvent) + Send + Sync>;
+
+// Where and how stage progress is shown
+#[derive(Default, Clone)]
+pub(crate) struct ProgressSettings {
+    pub(crate) callback: Option<Arc<ProgressCallback>>,
+    #[cfg(feature = "progress")]
+    pub(crate) style: Option<ProgressStyle>,
+    // Keeps the bars of all stages on screen, with list pages on their own bar
+    #[cfg(feature = "progress")]
+    pub(crate) multi: Option<MultiProgress>,
+}
+
+// Reports stage progress to the callback if set, otherwise to a
+// terminal progress bar (only with the `progress` feature)
+pub(crate) struct Progress<'a> {
+    #[cfg(feature = "progress")]
+    bar: Option<ProgressBar>,
+    #[cfg(feature = "progress")]
+    pages_bar: Option<ProgressBar>,
+    #[cfg(feature = "progress")]
+    message: String,
+    callback: Option<&'a ProgressCallback>,
+    event: fn(usize, usize) -> ProgressEvent,
+    current: AtomicUsize,
+    total: usize,
+    pages_done: AtomicUsize,
+    pages_total: AtomicUsize,
+}
+
+impl<'a> Progress<'a> {
+    pub(crate) fn new(
+        total: usize,
+        message: &str,
+        settings: &'a ProgressSettings,
+        event: fn(usize, usize) -> ProgressEvent,
+    ) -> Self {
+        #[cfg(not(feature = "progress"))]
+        let _ = message;
+        let callback = settings.callback.as_deref();
+        #[cfg(feature = "progress")]
+        let bar = callback
+            .is_none()
+            .then(|| progress_bar(total, message, settings.style.as_ref()))
+            .map(|bar| match &settings.multi {
+                Some(multi) => multi.add(bar),
+                None => bar,
+            });
+        Self {
+            #[cfg(feature = "progress")]
+            bar,
+            #[cfg(feature = "progress")]
+            pages_bar: None,
+            #[cfg(feature = "progress")]
+            message: message.to_string(),
+            callback,
+            event,
+            current: AtomicUsize::new(0),
+            total,
+            pages_done: AtomicUsize::new(0),
+            pages_total: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn inc(&self) {
+        let current = self.current.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(callback) = self.callback {
+            callback((self.event)(current, self.total));
+        }
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.bar {
+            bar.inc(1);
+        }
+    }
+
+    // Show pages on a bar of their own under the stage bar, only when
+    // the bars are shown together
+    pub(crate) fn with_pages_bar(self, settings: &ProgressSettings) -> Self {
+        #[cfg(feature = "progress")]
+        if let (Some(multi), Some(_)) = (&settings.multi, &self.bar) {
+            let bar = progress_bar(0, "Страницы категорий", settings.style.as_ref());
+            return Self {
+                pages_bar: Some(multi.add(bar)),
+                ..self
+            };
+        }
+        #[cfg(not(feature = "progress"))]
+        let _ = settings;
+        self
+    }
+
+    // Pages found inside the current items, e.g. category pagination
+    pub(crate) fn add_pages(&self, count: usize) {
+        self.pages_total.fetch_add(count, Ordering::Relaxed);
+        self.report_pages(self.pages_done.load(Ordering::Relaxed));
+    }
+
+    pub(crate) fn page_done(&self) {
+        let done = self.pages_done.fetch_add(1, Ordering::Relaxed) + 1;
+        self.report_pages(done);
+    }
+
+    fn report_pages(&self, done: usize) {
+        let total = self.pages_total.load(Ordering::Relaxed);
+        if let Some(callback) = self.callback {
+            callback(ProgressEvent::PageDone {
+                current: done,
+                total,
+            });
+        }
+        #[cfg(feature = "progress")]
+        if let Some(bar) = &self.pages_bar {
+            bar.set_length(total as u64);
+            bar.set_position(done as u64);
+        } else if let Some(bar) = &self.bar {
+            bar.set_message(format!("{} [страниц {}/{}]", self.message, done, total));
+        }
+    }
+
+    pub(crate) fn finish(&self) {
+        #[cfg(feature = "progress")]
+        for bar in self.bar.iter().chain(&self.pages_bar) {
+            bar.finish();
+        }
+    }
+}
+
+#[cfg(feature = "progress")]
+fn progress_bar(total: usize, message: &str, style: Option<&ProgressStyle>) -> ProgressBar {
+    let sty = style.cloned().unwrap_or_else(|| {
+        ProgressStyle::default_bar()
+            .template("{msg} {wide_bar:.cyan/blue} {pos}/{len}")
+            .unwrap()
+            .progress_chars("##-")
+    });
+    let bar = ProgressBar::new(total as u64);
+    bar.set_style(sty);
+    bar.set_message(message.to_string());
+    bar
+}
@@ -0,0 +1,67 @@
+use anyhow::{bail, Result};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::time::Duration;
+use tokio::{sync::Mutex, time::Instant};
+
+// Shared limiter: every request reserves the next free time slot,
+// so the limit applies to all concurrent tasks together
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next_slot: Mutex<Instant>,
+}
+
+// One request an hour, slower limits are most likely a mistaken unit
+const MIN_REQUESTS_PER_SECOND: f64 = 1.0 / 3600.0;
+
+impl RateLimiter {
+    pub(crate) fn new(requests_per_second: f64) -> Result<Self> {
+        if !(requests_per_second.is_finite() && requests_per_second >= MIN_REQUESTS_PER_SECOND) {
+            bail!(
+                "Invalid rate limit {} requests per second, expected a finite number of at least {}",
+                requests_per_second,
+                MIN_REQUESTS_PER_SECOND
+            );
+        }
+        Ok(Self {
+            interval: Duration::from_secs_f64(1.0 / requests_per_second),
+            next_slot: Mutex::new(Instant::now()),
+        })
+    }
+
+    pub(crate) async fn wait(&self) {
+        let mut next_slot = self.next_slot.lock().await;
+        let slot = (*next_slot).max(Instant::now());
+        *next_slot = slot + self.interval;
+        drop(next_slot);
+        tokio::time::sleep_until(slot).await;
+    }
+}
+
+// Random delay before each request, so concurrent tasks don't fire in
+// lockstep
+pub(crate) struct Jitter {
+    max: Duration,
+    rng: std::sync::Mutex<StdRng>,
+}
+
+impl Jitter {
+    pub(crate) fn new(max: Duration, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            max,
+            rng: std::sync::Mutex::new(rng),
+        }
+    }
+
+    pub(crate) fn next_delay(&self) -> Duration {
+        let fraction: f64 = self.rng.lock().unwrap().gen();
+        self.max.mul_f64(fraction)
+    }
+
+    pub(crate) async fn wait(&self) {
+        tokio::time::sleep(self.next_delay()).await;
+    }
+}
@@ -0,0 +1,199 @@
+// Minimal robots.txt parser: User-agent groups with Allow/Disallow rules,
+// `*` wildcards and `$` end anchors, longest match wins
+pub(crate) struct Robots {
+    rules: Vec<Rule>,
+}
+
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+#[derive(Default)]
+struct Group {
+    agents: Vec<String>,
+    rules: Vec<Rule>,
+}
+
+impl Robots {
+    pub(crate) fn allow_all() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    pub(crate) fn parse(content: &str, user_agent: &str) -> Self {
+        let mut groups: Vec<Group> = Vec::new();
+        let mut in_rules = true;
+        for line in content.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next().unwrap_or_default().trim().to_lowercase();
+            let value = parts.next().unwrap_or_default().trim();
+            match key.as_str() {
+                "user-agent" => {
+                    // User-agent after rules starts a new group
+                    if in_rules || groups.is_empty() {
+                        groups.push(Group::default());
+                        in_rules = false;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.agents.push(value.to_lowercase());
+                    }
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    if value.is_empty() {
+                        continue;
+                    }
+                    if let Some(group) = groups.last_mut() {
+                        group.rules.push(Rule {
+                            allow: key == "allow",
+                            pattern: value.to_string(),
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Prefer groups naming our product token, otherwise use `*` groups
+        let product = product_token(user_agent);
+        let (specific, generic): (Vec<Group>, Vec<Group>) = groups
+            .into_iter()
+            .filter(|group| !group.agents.is_empty())
+            .partition(|group| {
+                group.agents.iter().any(|agent| {
+                    agent != "*" && !product.is_empty() && product_token(agent) == product
+                })
+            });
+        let selected = if specific.is_empty() {
+            generic
+                .into_iter()
+                .filter(|group| group.agents.iter().any(|agent| agent == "*"))
+                .collect()
+        } else {
+            specific
+        };
+        Self {
+            rules: selected.into_iter().flat_map(|group| group.rules).collect(),
+        }
+    }
+
+    pub(crate) fn is_allowed(&self, url: &str) -> bool {
+        let path = match url::Url::parse(url) {
+            Ok(url) => match url.query() {
+                Some(query) => format!("{}?{}", url.path(), query),
+                None => url.path().to_string(),
+            },
+            Err(_) => url.to_string(),
+        };
+        self.rules
+            .iter()
+            .filter(|rule| pattern_matches(&rule.pattern, &path))
+            .max_by_key(|rule| (rule.pattern.len(), rule.allow))
+            .map(|rule| rule.allow)
+            .unwrap_or(true)
+    }
+}
+
+// `Name/1.0 (+url)` -> `name`
+fn product_token(user_agent: &str) -> String {
+    user_agent
+        .split(|c: char| c == '/' || c.is_whitespace())
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+fn pattern_matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    if !path.starts_with(first) {
+        return false;
+    }
+    let mut rest = &path[first.len()..];
+    let parts = parts.collect::<Vec<&str>>();
+    for (i, part) in parts.iter().enumerate() {
+        let is_last = i + 1 == parts.len();
+        if is_last && anchored {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGENT: &str = "vstu-scraping-rs/0.1";
+
+    fn allowed(content: &str, path: &str) -> bool {
+        Robots::parse(content, AGENT).is_allowed(&format!("https://example.com{}", path))
+    }
+
+    #[test]
+    fn empty_file_allows_everything() {
+        assert!(allowed("", "/"));
+        assert!(allowed("# nothing here\n", "/plants/"));
+    }
+
+    #[test]
+    fn longest_match_wins() {
+        let content = "User-agent: *\nDisallow: /plants/\nAllow: /plants/ficus/\n";
+        assert!(!allowed(content, "/plants/"));
+        assert!(!allowed(content, "/plants/cactus/"));
+        assert!(allowed(content, "/plants/ficus/"));
+        assert!(allowed(content, "/news/"));
+        // Allow wins a tie of equal length
+        let content = "User-agent: *\nDisallow: /a\nAllow: /a\n";
+        assert!(allowed(content, "/a"));
+    }
+
+    #[test]
+    fn wildcards_and_end_anchor() {
+        let content = "User-agent: *\nDisallow: /*.jpg$\nDisallow: /search*sort=\n";
+        assert!(!allowed(content, "/images/a.jpg"));
+        assert!(allowed(content, "/images/a.jpg.html"));
+        assert!(!allowed(content, "/search?q=1&sort=name"));
+        assert!(allowed(content, "/search?q=1"));
+        let content = "User-agent: *\nDisallow: /$\n";
+        assert!(!allowed(content, "/"));
+        assert!(allowed(content, "/plants/"));
+    }
+
+    #[test]
+    fn own_group_overrides_wildcard_group() {
+        let content = "User-agent: *\nDisallow: /\n\n\
+                       User-agent: VSTU-Scraping-RS\nDisallow: /private/\n";
+        assert!(allowed(content, "/plants/"));
+        assert!(!allowed(content, "/private/"));
+    }
+
+    #[test]
+    fn falls_back_to_wildcard_group() {
+        let content = "User-agent: googlebot\nDisallow: /\n\n\
+                       User-agent: *\nDisallow: /private/\n";
+        assert!(allowed(content, "/plants/"));
+        assert!(!allowed(content, "/private/"));
+    }
+
+    #[test]
+    fn agent_must_match_the_whole_product_token() {
+        // A prefix or part of our token names some other crawler
+        let content = "User-agent: vstu\nDisallow: /\n\n\
+                       User-agent: *\nDisallow: /private/\n";
+        assert!(allowed(content, "/plants/"));
+        let content = "User-agent: vstu-scraping-rs-extra\nDisallow: /\n";
+        assert!(allowed(content, "/plants/"));
+        let content = "User-agent: vstu-scraping-rs/2.0\nDisallow: /\n";
+        assert!(!allowed(content, "/plants/"));
+    }
+}
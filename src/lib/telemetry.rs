@@ -0,0 +1,43 @@
+// Metrics recorded with the `metrics` crate when the `metrics` feature is
+// on, no-ops otherwise. The names are documented in README.md, keep the
+// list there in sync.
+use std::time::Duration;
+
+#[cfg(feature = "metrics")]
+use metrics::{counter, histogram};
+
+pub(crate) fn request(duration: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        counter!("scraper_requests_total").increment(1);
+        histogram!("scraper_request_duration_seconds").record(duration.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = duration;
+}
+
+pub(crate) fn retry() {
+    #[cfg(feature = "metrics")]
+    counter!("scraper_retries_total").increment(1);
+}
+
+pub(crate) fn image_bytes(bytes: usize) {
+    #[cfg(feature = "metrics")]
+    counter!("scraper_image_bytes_total").increment(bytes as u64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = bytes;
+}
+
+pub(crate) fn parse_failures(count: usize) {
+    #[cfg(feature = "metrics")]
+    counter!("scraper_parse_failures_total").increment(count as u64);
+    #[cfg(not(feature = "metrics"))]
+    let _ = count;
+}
+
+pub(crate) fn stage(stage: &'static str, duration: Duration) {
+    #[cfg(feature = "metrics")]
+    histogram!("scraper_stage_duration_seconds", "stage" => stage).record(duration.as_secs_f64());
+    #[cfg(not(feature = "metrics"))]
+    let _ = (stage, duration);
+}
@@ -0,0 +1,145 @@
+use crate::Attribute;
+
+lazy_static::lazy_static! {
+    static ref NUMBER_RE: regex::Regex = regex::Regex::new(r"\d+(?:[.,]\d+)?").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TempUnit {
+    Celsius,
+    Fahrenheit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureRange {
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub unit: TempUnit,
+}
+
+impl Attribute {
+    /// Numeric range from a value like "18–24 °C" or "не ниже 15".
+    /// Several ranges (e.g. summer and winter) are merged into one.
+    pub fn as_temperature_range(&self) -> Option<TemperatureRange> {
+        parse_temperature(&self.value)
+    }
+}
+
+fn parse_temperature(value: &str) -> Option<TemperatureRange> {
+    let text = value.to_lowercase();
+    let numbers = NUMBER_RE
+        .find_iter(&text)
+        .filter_map(|number| {
+            let parsed = number.as_str().replace(',', ".").parse::<f32>().ok()?;
+            Some(if is_negative(&text[..number.start()]) {
+                -parsed
+            } else {
+                parsed
+            })
+        })
+        .collect::<Vec<f32>>();
+
+    let (min, max) = match numbers.as_slice() {
+        [] => return None,
+        [single] => single_bound(&text, *single),
+        _ => (
+            numbers.iter().copied().reduce(f32::min),
+            numbers.iter().copied().reduce(f32::max),
+        ),
+    };
+    let unit = if ["°f", "℉", "фаренгейт"].iter().any(|m| text.contains(m)) {
+        TempUnit::Fahrenheit
+    } else {
+        TempUnit::Celsius
+    };
+    Some(TemperatureRange { min, max, unit })
+}
+
+// Minus sign, unless it separates a range like "18-24"
+fn is_negative(before: &str) -> bool {
+    let mut chars = before.chars().rev();
+    match chars.next() {
+        Some('-') | Some('−') => {}
+        _ => return false,
+    }
+    match chars.find(|c| !c.is_whitespace()) {
+        Some(c) => !(c.is_ascii_digit() || c == '°' || c == 'c' || c == 'с'),
+        None => true,
+    }
+}
+
+// "не ниже 15" is a lower bound, "до 25" an upper one, "20" both
+fn single_bound(text: &str, value: f32) -> (Option<f32>, Option<f32>) {
+    let has = |words: &[&str]| words.iter().any(|word| text.contains(word));
+    if has(&["не ниже", "не менее", "от "]) {
+        (Some(value), None)
+    } else if has(&["не выше", "не более", "до "]) {
+        (None, Some(value))
+    } else if has(&["выше"]) {
+        (Some(value), None)
+    } else if has(&["ниже"]) {
+        (None, Some(value))
+    } else {
+        (Some(value), Some(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(min: Option<f32>, max: Option<f32>) -> Option<TemperatureRange> {
+        Some(TemperatureRange {
+            min,
+            max,
+            unit: TempUnit::Celsius,
+        })
+    }
+
+    #[test]
+    fn parses_ranges_and_bounds() {
+        assert_eq!(parse_temperature("18–24 °C"), range(Some(18.0), Some(24.0)));
+        assert_eq!(parse_temperature("18-24"), range(Some(18.0), Some(24.0)));
+        assert_eq!(parse_temperature("не ниже 15"), range(Some(15.0), None));
+        assert_eq!(parse_temperature("до 25 °C"), range(None, Some(25.0)));
+        assert_eq!(
+            parse_temperature("около 20,5"),
+            range(Some(20.5), Some(20.5))
+        );
+        assert_eq!(
+            parse_temperature("летом 20–25, зимой 12–15"),
+            range(Some(12.0), Some(25.0))
+        );
+        assert_eq!(
+            parse_temperature("от -5 до 10 °C"),
+            range(Some(-5.0), Some(10.0))
+        );
+        let fahrenheit = parse_temperature("65–75 °F").unwrap();
+        assert_eq!(fahrenheit.unit, TempUnit::Fahrenheit);
+    }
+
+    #[test]
+    fn malformed_values_are_none() {
+        assert_eq!(parse_temperature(""), None);
+        assert_eq!(parse_temperature("тепло"), None);
+        assert_eq!(parse_temperature("комнатная, без сквозняков"), None);
+    }
+
+    #[test]
+    fn minus_sign_or_range_dash() {
+        assert!(is_negative("-"));
+        assert!(is_negative("от −"));
+        assert!(!is_negative("18-"));
+        assert!(!is_negative("18 °c -"));
+        assert!(!is_negative("от "));
+    }
+
+    #[test]
+    fn single_value_bounds() {
+        assert_eq!(single_bound("не менее 10", 10.0), (Some(10.0), None));
+        assert_eq!(single_bound("не выше 30", 30.0), (None, Some(30.0)));
+        assert_eq!(single_bound("выше 16", 16.0), (Some(16.0), None));
+        assert_eq!(single_bound("ниже 28", 28.0), (None, Some(28.0)));
+        assert_eq!(single_bound("20 °c", 20.0), (Some(20.0), Some(20.0)));
+    }
+}
@@ -0,0 +1,326 @@
+// Fixtures shared by the unit tests
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, Once},
+    time::Duration,
+};
+
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use crate::{Attribute, Attributes, Houseplant};
+
+pub(crate) fn attribute(parameter: &str, value: &str) -> Option<Attribute> {
+    Some(Attribute {
+        parameter: parameter.to_string(),
+        value: value.to_string(),
+    })
+}
+
+// Plant with every attribute set, as read back from a database
+pub(crate) fn sample_plant(name: &str, source_url: &str) -> Houseplant {
+    Houseplant {
+        name: name.to_string(),
+        source_url: source_url.to_string(),
+        image_filename: Some("abc.jpg".to_string()),
+        thumbnail: Some("abc_100x100.jpg".to_string()),
+        html_dump: None,
+        image_bytes: None,
+        attribute_sources: None,
+        image_url: "https://example.com/abc.jpg".to_string(),
+        attributes: Attributes {
+            temperature: attribute("Температура", "18–24 °C"),
+            humidity: attribute("Влажность", "высокая"),
+            illumination: attribute("Освещение", "яркий рассеянный свет"),
+            watering: attribute("Полив", "летом обильный, зимой умеренный"),
+            soil: attribute("Грунт", "лёгкий, с песком"),
+            fertilizer: attribute("Подкормка", "раз в две недели"),
+            transplant: attribute("Пересадка", "весной"),
+            propagation: attribute("Размножение", "черенками"),
+            features: attribute("Особенности", "ядовит"),
+        },
+    }
+}
+
+// Care table rows of a typical plant page
+pub(crate) const CARE_ROWS: [(&str, &str); 3] = [
+    ("Температура", "18–24 °C"),
+    ("Полив", "умеренный"),
+    ("Освещение", "рассеянный свет"),
+];
+
+pub(crate) fn table(rows: &[(&str, &str)]) -> String {
+    let rows = rows
+        .iter()
+        .map(|(parameter, value)| format!("<tr><td>{}</td><td>{}</td></tr>", parameter, value))
+        .collect::<String>();
+    format!("<table><tbody>{}</tbody></table>", rows)
+}
+
+// Plant page in the markup of komnatnie-rastenija.ru
+pub(crate) fn plant_page(title: &str, image_url: &str, rows: &[(&str, &str)]) -> String {
+    format!(
+        r#"<html><body><article>
+        <h1 class="entry-title">{}</h1>
+        <img itemprop="url image" data-src="{}">
+        {}
+        </article></body></html>"#,
+        title,
+        image_url,
+        table(rows)
+    )
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Request {
+    pub(crate) path: String,
+    headers: Vec<(String, String)>,
+}
+
+impl Request {
+    pub(crate) fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Response {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    delay: Duration,
+}
+
+impl Response {
+    pub(crate) fn html(body: &str) -> Self {
+        Self::bytes("text/html; charset=UTF-8", body.as_bytes().to_vec())
+    }
+
+    pub(crate) fn bytes(content_type: &str, body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            body,
+            delay: Duration::ZERO,
+        }
+        .header("Content-Type", content_type)
+    }
+
+    pub(crate) fn status(status: u16) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub(crate) fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    // Wait before answering, to reorder concurrent requests
+    pub(crate) fn delayed(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+}
+
+type Handler = Arc<dyn Fn(&Request) -> Response + Send + Sync>;
+
+// HTTP/1.1 server on a random local port, one request per connection.
+// Unknown paths get 404, every request is recorded.
+#[derive(Clone)]
+pub(crate) struct TestServer {
+    base_url: String,
+    routes: Arc<Mutex<HashMap<String, Handler>>>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    pub(crate) async fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = Self {
+            base_url: format!("http://{}", listener.local_addr().unwrap()),
+            routes: Arc::default(),
+            requests: Arc::default(),
+        };
+        let handle = server.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(handle.clone().serve(stream));
+            }
+        });
+        server
+    }
+
+    async fn serve(self, mut stream: TcpStream) {
+        let mut head = Vec::new();
+        let mut buf = [0; 4096];
+        while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+            match stream.read(&mut buf).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => head.extend_from_slice(&buf[..n]),
+            }
+        }
+        let head = String::from_utf8_lossy(&head).into_owned();
+        let mut lines = head.lines();
+        let path = lines
+            .next()
+            .and_then(|line| line.split(' ').nth(1))
+            .unwrap_or_default()
+            .to_string();
+        let headers = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.to_string(), value.trim().to_string()))
+            .collect();
+        let request = Request { path, headers };
+        self.requests.lock().unwrap().push(request.clone());
+        let handler = self.routes.lock().unwrap().get(&request.path).cloned();
+        let response = match handler {
+            Some(handler) => handler(&request),
+            None => Response::status(404),
+        };
+        tokio::time::sleep(response.delay).await;
+
+        let mut output = format!("HTTP/1.1 {} X\r\n", response.status);
+        for (name, value) in &response.headers {
+            output.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        output.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            response.body.len()
+        ));
+        let mut output = output.into_bytes();
+        output.extend_from_slice(&response.body);
+        let _ = stream.write_all(&output).await;
+        let _ = stream.shutdown().await;
+    }
+
+    pub(crate) fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub(crate) fn route(&self, path: &str, response: Response) {
+        self.route_fn(path, move |_| response.clone());
+    }
+
+    pub(crate) fn route_fn<F>(&self, path: &str, handler: F)
+    where
+        F: Fn(&Request) -> Response + Send + Sync + 'static,
+    {
+        let mut routes = self.routes.lock().unwrap();
+        routes.insert(path.to_string(), Arc::new(handler));
+    }
+
+    pub(crate) fn hits(&self, path: &str) -> usize {
+        let requests = self.requests.lock().unwrap();
+        requests
+            .iter()
+            .filter(|request| request.path == path)
+            .count()
+    }
+}
+
+// Smallest byte string that passes as a complete JPEG, distinct per `seed`
+pub(crate) fn fake_jpeg(seed: &str) -> Vec<u8> {
+    let mut bytes = vec![0xFF, 0xD8, 0xFF, 0xE0];
+    bytes.extend_from_slice(seed.as_bytes());
+    bytes.extend_from_slice(&[0xFF, 0xD9]);
+    bytes
+}
+
+pub(crate) fn title_page(category_paths: &[&str]) -> String {
+    let items = category_paths
+        .iter()
+        .map(|path| {
+            format!(
+                r#"<li class="cat-item"><a href="{}">{}</a></li>"#,
+                path, path
+            )
+        })
+        .collect::<String>();
+    format!("<html><body><ul>{}</ul></body></html>", items)
+}
+
+pub(crate) fn category_page(plant_paths: &[&str]) -> String {
+    let links = plant_paths
+        .iter()
+        .map(|path| format!(r#"<a itemprop="url" href="{}">{}</a>"#, path, path))
+        .collect::<String>();
+    format!("<html><body>{}</body></html>", links)
+}
+
+// Site with a title page at `/`, one list page per category and plant
+// pages `/<plant>/` named after their slug with an image `/img/<plant>.jpg`
+pub(crate) async fn test_site(categories: &[(&str, &[&str])]) -> TestServer {
+    let server = TestServer::start().await;
+    let category_paths = categories
+        .iter()
+        .map(|(category, _)| format!("/{}/", category))
+        .collect::<Vec<_>>();
+    let category_paths = category_paths
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    server.route("/", Response::html(&title_page(&category_paths)));
+    for (category, plants) in categories {
+        let plant_paths = plants
+            .iter()
+            .map(|plant| format!("/{}/", plant))
+            .collect::<Vec<_>>();
+        let plant_paths = plant_paths.iter().map(String::as_str).collect::<Vec<_>>();
+        // List pages are fetched as `page/N`, the first one too
+        let page = Response::html(&category_page(&plant_paths));
+        server.route(&format!("/{}/", category), page.clone());
+        server.route(&format!("/{}/page/1", category), page);
+        for plant in plants.iter() {
+            let image_path = format!("/img/{}.jpg", plant);
+            let page = plant_page(plant, &image_path, &CARE_ROWS);
+            server.route(&format!("/{}/", plant), Response::html(&page));
+            server.route(&image_path, Response::bytes("image/jpeg", fake_jpeg(plant)));
+        }
+    }
+    server
+}
+
+// Log records of all tests, filter them by something unique to the test
+static LOGS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct TestLogger;
+
+impl log::Log for TestLogger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        let line = format!("{} {}", record.level(), record.args());
+        LOGS.lock().unwrap().push(line);
+    }
+
+    fn flush(&self) {}
+}
+
+// Start recording log records, call before the code under test
+pub(crate) fn capture_logs() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&TestLogger).unwrap();
+        log::set_max_level(log::LevelFilter::Debug);
+    });
+}
+
+pub(crate) fn logged(fragment: &str) -> Vec<String> {
+    let logs = LOGS.lock().unwrap();
+    logs.iter()
+        .filter(|line| line.contains(fragment))
+        .cloned()
+        .collect()
+}
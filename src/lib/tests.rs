@@ -0,0 +1,1199 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use database::{Database, InMemoryDatabase};
+
+use super::*;
+use crate::test_util::*;
+
+type TestScraper = Scraper<InMemoryDatabase>;
+
+// Scraper of a test site, without images unless they are enabled again
+fn site_scraper<T: Database>(server: &TestServer) -> Scraper<T> {
+    Scraper::default()
+        .with_base_url(&server.url("/"))
+        .with_images(false)
+        .with_max_retries(0)
+}
+
+// Rejects plants whose URL contains `fail_on`, stores the rest
+#[derive(Default)]
+struct FlakyDatabase {
+    plants: InMemoryDatabase,
+    fail_on: Option<String>,
+    flushes: AtomicUsize,
+}
+
+impl FlakyDatabase {
+    fn failing_on(pattern: &str) -> Self {
+        Self {
+            fail_on: Some(pattern.to_string()),
+            ..Self::default()
+        }
+    }
+}
+
+#[async_trait]
+impl Database for FlakyDatabase {
+    async fn insert(&self, plant: &Houseplant) -> Result<()> {
+        match &self.fail_on {
+            Some(pattern) if plant.source_url.contains(pattern.as_str()) => {
+                Err(anyhow!("UNIQUE constraint failed"))
+            }
+            _ => self.plants.insert(plant).await,
+        }
+    }
+
+    async fn exists(&self, plant_url: &str) -> Result<bool> {
+        self.plants.exists(plant_url).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.flushes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn get_all(&self) -> Result<Vec<Houseplant>> {
+        self.plants.get_all().await
+    }
+}
+
+#[test]
+fn page_without_title_is_an_error() {
+    let html = format!(
+        r#"<html><body><img itemprop="url image" data-src="/a.jpg">{}</body></html>"#,
+        table(&CARE_ROWS)
+    );
+    let scraper = TestScraper::default();
+    let result = scraper.parse_houseplant_html(&html, "https://example.com/a/");
+    assert!(matches!(result, Err(ScrapeError::MissingElement { what }) if what == "title"));
+
+    let html = plant_page("Алоэ", "/a.jpg", &CARE_ROWS);
+    let plant = scraper.parse_houseplant_html(&html, "https://example.com/a/");
+    assert_eq!(plant.unwrap().name, "Алоэ");
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trip() {
+    let plant = Houseplant {
+        html_dump: Some("pages/ficus.html".to_string()),
+        attribute_sources: Some(vec![AttributeSource {
+            row: 0,
+            parameter: "Температура".to_string(),
+            field: Some("temperature".to_string()),
+        }]),
+        ..sample_plant("Фикус", "https://example.com/ficus/")
+    };
+    let json = serde_json::to_string(&plant).unwrap();
+    assert!(json.contains(r#""image_filename":"abc.jpg""#));
+    assert_eq!(serde_json::from_str::<Houseplant>(&json).unwrap(), plant);
+
+    // Missing attributes are left out
+    let plant = Houseplant {
+        attributes: Attributes::default(),
+        ..plant
+    };
+    let json = serde_json::to_string(&plant).unwrap();
+    assert!(json.contains(r#""attributes":{}"#));
+    assert_eq!(serde_json::from_str::<Houseplant>(&json).unwrap(), plant);
+}
+
+#[tokio::test]
+async fn invalid_rate_limit_fails_requests() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+    for requests_per_second in [1e-300, f64::NAN, f64::INFINITY] {
+        let scraper =
+            site_scraper::<InMemoryDatabase>(&server).with_rate_limit(requests_per_second);
+        assert!(scraper.scraper().await.is_err());
+    }
+    assert_eq!(server.hits("/"), 0);
+
+    // A valid limit or disabling it replaces the rejected value
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_rate_limit(f64::NAN);
+    assert_eq!(
+        scraper.with_rate_limit(0.0).scraper().await.unwrap().len(),
+        1
+    );
+}
+
+#[test]
+fn retry_delay_is_capped() {
+    assert_eq!(retry_delay(0), Duration::from_millis(200));
+    assert_eq!(retry_delay(2), Duration::from_millis(800));
+    assert_eq!(retry_delay(20), Duration::from_secs(30));
+    assert_eq!(retry_delay(64), Duration::from_secs(30));
+    assert_eq!(retry_delay(usize::MAX), Duration::from_secs(30));
+}
+
+#[test]
+fn dedup_urls_keeps_original_urls() {
+    let mut urls = vec![
+        "https://x/p/".to_string(),
+        "https://x/q/?b=2&a=1".to_string(),
+        "https://x/p".to_string(),
+        "https://x/p#top".to_string(),
+    ];
+    dedup_urls(&mut urls);
+    assert_eq!(urls, vec!["https://x/p", "https://x/q/?b=2&a=1"]);
+
+    let mut urls = vec!["https://x/p/".to_string(), "https://x/p/#top".to_string()];
+    dedup_urls(&mut urls);
+    assert_eq!(urls, vec!["https://x/p/"]);
+}
+
+#[tokio::test]
+async fn resumes_from_checkpoint() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.txt");
+    // An interrupted run got as far as the first plant
+    std::fs::write(&path, format!("{}\n", server.url("/p1/"))).unwrap();
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_checkpoint(path.to_str().unwrap());
+    let plants = scraper.scraper().await.unwrap();
+
+    let names = plants
+        .iter()
+        .map(|plant| plant.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["p2", "p3"]);
+    assert_eq!(server.hits("/p1/"), 0);
+    // The run completed, the next one starts over
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn checkpoint_skips_plants_not_stored() {
+    let server = test_site(&[("cat", &["p1", "p2-fail"])]).await;
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("checkpoint.txt");
+    let scraper = Scraper {
+        database: Some(FlakyDatabase::failing_on("fail")),
+        ..site_scraper(&server)
+    };
+    let urls = vec![server.url("/p1/"), server.url("/p2-fail/")];
+
+    let mut checkpoint = Checkpoint::load(&path).await.unwrap();
+    let token = CancellationToken::new();
+    let report = scraper
+        .scrape_plants(urls, &token, false, true, Some(&mut checkpoint))
+        .await
+        .unwrap();
+
+    assert_eq!(report.db_failures.len(), 1);
+    assert!(checkpoint.contains(&server.url("/p1/")));
+    assert!(!checkpoint.contains(&server.url("/p2-fail/")));
+    let saved = std::fs::read_to_string(&path).unwrap();
+    assert_eq!(saved, format!("{}\n", server.url("/p1/")));
+}
+
+#[tokio::test]
+async fn html_cache_serves_pages_until_refreshed() {
+    let server = TestServer::start().await;
+    server.route("/p/", Response::html("<p>сайт</p>"));
+    let dir = tempfile::tempdir().unwrap();
+    let cache_dir = dir.path().to_str().unwrap();
+    let url = server.url("/p/");
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_html_cache(cache_dir);
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "<p>сайт</p>");
+    server.route("/p/", Response::html("<p>новая</p>"));
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "<p>сайт</p>");
+    assert_eq!(server.hits("/p/"), 1);
+
+    // Refresh set before the cache itself still applies
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_html_cache_refresh(true)
+        .with_html_cache(cache_dir);
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "<p>новая</p>");
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_html_cache(cache_dir);
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "<p>новая</p>");
+    assert_eq!(server.hits("/p/"), 2);
+}
+
+#[tokio::test]
+async fn invalid_proxy_fails_requests() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_proxy("ftp://proxy.example");
+    assert!(scraper.scraper().await.is_err());
+    let built = TestScraper::builder().proxy("ftp://proxy.example").build();
+    assert!(matches!(built, Err(ScrapeError::InvalidConfig(_))));
+
+    // A valid proxy nobody listens on, requests must not go around it
+    let port = std::net::TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let scraper =
+        site_scraper::<InMemoryDatabase>(&server).with_proxy(&format!("http://127.0.0.1:{}", port));
+    assert!(scraper.scraper().await.is_err());
+    assert_eq!(server.hits("/"), 0);
+}
+
+#[tokio::test]
+async fn sends_accept_language() {
+    let server = TestServer::start().await;
+    server.route_fn("/p/", |request| {
+        Response::html(request.header("Accept-Language").unwrap_or_default())
+    });
+    let url = server.url("/p/");
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "ru");
+    let scraper = scraper.with_accept_language("en-US,en;q=0.8");
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "en-US,en;q=0.8");
+
+    // Not a valid header value
+    let scraper = scraper.with_accept_language("ru\nen");
+    assert!(scraper.fetch_html(&url).await.is_err());
+    let scraper = scraper.with_accept_language("de");
+    assert_eq!(scraper.fetch_html(&url).await.unwrap(), "de");
+    assert_eq!(server.hits("/p/"), 3);
+}
+
+#[tokio::test]
+async fn warm_up_tells_failed_step() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+    scraper.warm_up().await.unwrap();
+
+    server.route("/cat/", Response::html(&category_page(&[])));
+    let result = scraper.warm_up().await;
+    assert!(
+        matches!(result, Err(ScrapeError::MissingElement { what }) if what.starts_with("plant links"))
+    );
+
+    server.route("/", Response::html(&title_page(&[])));
+    let result = scraper.warm_up().await;
+    assert!(
+        matches!(result, Err(ScrapeError::MissingElement { what }) if what.starts_with("categories"))
+    );
+
+    server.route("/", Response::status(404));
+    assert!(matches!(scraper.warm_up().await, Err(ScrapeError::Http(_))));
+}
+
+#[tokio::test]
+async fn stream_flushes_database_at_the_end() {
+    let server = test_site(&[("cat", &["p1", "p2"])]).await;
+    let scraper = Scraper {
+        database: Some(FlakyDatabase::default()),
+        ..site_scraper(&server)
+    };
+
+    let plants = scraper.scraper_stream().collect::<Vec<_>>().await;
+    assert_eq!(plants.len(), 2);
+    assert!(plants.iter().all(Result::is_ok));
+    let db = scraper.database.as_ref().unwrap();
+    assert_eq!(db.flushes.load(Ordering::SeqCst), 1);
+    assert_eq!(db.get_all().await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn concurrent_downloads_keep_every_image() {
+    let plants = (0..20).map(|i| format!("p{}", i)).collect::<Vec<_>>();
+    let plants = plants.iter().map(String::as_str).collect::<Vec<_>>();
+    let server = test_site(&[("cat", &plants)]).await;
+    let dir = tempfile::tempdir().unwrap();
+
+    // Timestamp names of images downloaded in the same millisecond collide
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_concurrent_tasks(20)
+        .with_images(true)
+        .with_image_naming(ImageNaming::Timestamp)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()));
+    let plants = scraper.scraper().await.unwrap();
+
+    let mut names = plants
+        .iter()
+        .map(|plant| plant.image_filename.clone().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names.dedup();
+    assert_eq!(names.len(), 20);
+    let files = std::fs::read_dir(dir.path()).unwrap().count();
+    assert_eq!(files, 20);
+    for plant in &plants {
+        let name = plant.image_filename.as_ref().unwrap();
+        let stored = std::fs::read(dir.path().join(name)).unwrap();
+        assert_eq!(stored, fake_jpeg(&plant.name));
+    }
+}
+
+#[tokio::test]
+async fn keeps_image_url_and_filename() {
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let dir = tempfile::tempdir().unwrap();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_images(true)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()));
+
+    let plant = scraper.scrape_single(&server.url("/ficus/")).await.unwrap();
+    assert_eq!(plant.image_url, server.url("/img/ficus.jpg"));
+    let image_filename = plant.image_filename.unwrap();
+    assert!(image_filename.ends_with(".jpg"));
+    assert!(dir.path().join(image_filename).exists());
+}
+
+#[tokio::test]
+async fn zero_concurrent_tasks_does_not_hang() {
+    let server = test_site(&[("cat", &["p1", "p2"])]).await;
+    let scraper = TestScraper::new(0, "images", None)
+        .with_base_url(&server.url("/"))
+        .with_images(false);
+    let plants = tokio::time::timeout(Duration::from_secs(10), scraper.scraper())
+        .await
+        .expect("scraper hangs")
+        .unwrap();
+    assert_eq!(plants.len(), 2);
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_concurrent_tasks(0);
+    assert_eq!(scraper.concurrent_tasks, 1);
+    let built = TestScraper::builder().concurrent_tasks(0).build();
+    assert!(matches!(built, Err(ScrapeError::InvalidConfig(_))));
+}
+
+#[tokio::test]
+async fn builder_covers_all_options() {
+    let server = test_site(&[("cat", &["p1", "p2"]), ("other", &["p3"])]).await;
+    let scraper = TestScraper::builder()
+        .base_url(&server.url("/"))
+        .max_retries(0)
+        .images(false)
+        .category_filter(vec!["cat".to_string()])
+        .limit(Some(1))
+        .build()
+        .unwrap();
+    let plants = scraper.scraper().await.unwrap();
+    assert_eq!(plants.len(), 1);
+    assert_eq!(plants[0].name, "p1");
+
+    for built in [
+        TestScraper::builder().base_url("not a url").build(),
+        TestScraper::builder().rate_limit(f64::NAN).build(),
+        TestScraper::builder().rate_limit(0.0).build(),
+        TestScraper::builder().rate_limit(1e-300).build(),
+    ] {
+        assert!(matches!(built, Err(ScrapeError::InvalidConfig(_))));
+    }
+}
+
+#[test]
+fn unmatched_rows_all_go_to_features() {
+    let mut rows = CARE_ROWS.to_vec();
+    rows.push(("Родина", "тропики Азии"));
+    rows.push(("Высота", "до 2 м"));
+    let html = plant_page("Фикус", "/a.jpg", &rows);
+    let plant = TestScraper::default()
+        .parse_houseplant_html(&html, "https://example.com/ficus/")
+        .unwrap();
+
+    let features = plant.attributes.features.unwrap();
+    assert_eq!(features.parameter, "Родина");
+    assert_eq!(features.value, "тропики Азии\nВысота: до 2 м");
+}
+
+#[tokio::test]
+async fn category_filter_keeps_matching_categories() {
+    let server = test_site(&[
+        ("sukkulenty", &["aloe", "agave"]),
+        ("palmy", &["hamedorea"]),
+    ])
+    .await;
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_category_filter(vec!["sukkulenty".to_string()]);
+
+    let report = scraper.scraper_with_report().await.unwrap();
+    let mut names = report
+        .plants
+        .iter()
+        .map(|plant| plant.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["agave", "aloe"]);
+    assert_eq!(report.stats.categories, 1);
+    assert_eq!(server.hits("/palmy/page/1"), 0);
+}
+
+#[tokio::test]
+async fn limit_caps_parsed_plants() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3", "p4"])]).await;
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_limit(Some(2));
+
+    let report = scraper.scraper_with_report().await.unwrap();
+    let names = report
+        .plants
+        .iter()
+        .map(|plant| plant.name.as_str())
+        .collect::<Vec<_>>();
+    // Taken from the sorted URLs
+    assert_eq!(names, ["p1", "p2"]);
+    assert_eq!(report.stats.plant_urls, 4);
+    assert_eq!(server.hits("/p3/") + server.hits("/p4/"), 0);
+}
+
+#[test]
+fn strict_attributes_warn_about_missing_ones() {
+    capture_logs();
+    let rows = [("Полив", "умеренный"), ("Освещение", "рассеянный свет")];
+    let html = plant_page("Алоэ", "/a.jpg", &rows);
+    let url = "https://example.com/strict-aloe/";
+
+    let scraper = TestScraper::default();
+    scraper.parse_houseplant_html(&html, url).unwrap();
+    assert!(logged(url).is_empty());
+
+    let scraper = scraper.with_strict_attributes(true);
+    scraper.parse_houseplant_html(&html, url).unwrap();
+    let warnings = logged(url);
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("WARN"));
+    assert!(warnings[0].contains("temperature"));
+    assert!(!warnings[0].contains("watering"));
+}
+
+#[tokio::test]
+async fn plants_are_sorted_by_url() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    // The first page finishes last
+    let page = plant_page("p1", "/img/p1.jpg", &CARE_ROWS);
+    server.route(
+        "/p1/",
+        Response::html(&page).delayed(Duration::from_millis(300)),
+    );
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_concurrent_tasks(3);
+
+    for _ in 0..2 {
+        let plants = scraper.scraper().await.unwrap();
+        let names = plants
+            .iter()
+            .map(|plant| plant.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["p1", "p2", "p3"]);
+    }
+}
+
+#[tokio::test]
+async fn non_image_bytes_are_not_stored() {
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    server.route(
+        "/img/ficus.jpg",
+        Response::html("<html><body>Страница не найдена</body></html>"),
+    );
+    let dir = tempfile::tempdir().unwrap();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_images(true)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()));
+
+    let result = scraper.scrape_single(&server.url("/ficus/")).await;
+    assert!(matches!(result, Err(ScrapeError::ImageDownload(_))));
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn not_modified_image_is_not_written_again() {
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let version = Arc::new(std::sync::Mutex::new("v1"));
+    let current = version.clone();
+    server.route_fn("/img/ficus.jpg", move |request| {
+        let etag = format!("\"{}\"", current.lock().unwrap());
+        if request.header("If-None-Match") == Some(etag.as_str()) {
+            return Response::status(304);
+        }
+        Response::bytes("image/jpeg", fake_jpeg(&etag)).header("ETag", &etag)
+    });
+    let dir = tempfile::tempdir().unwrap();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_images(true)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()));
+    let images = || {
+        let mut names = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .filter(|name| name != ".http")
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+    let url = server.url("/ficus/");
+
+    let first = scraper.scrape_single(&url).await.unwrap();
+    assert_eq!(images(), [first.image_filename.clone().unwrap()]);
+    let second = scraper.scrape_single(&url).await.unwrap();
+    assert_eq!(second.image_filename, first.image_filename);
+    assert_eq!(images().len(), 1);
+
+    // A changed image is downloaded again
+    *version.lock().unwrap() = "v2";
+    let third = scraper.scrape_single(&url).await.unwrap();
+    assert_ne!(third.image_filename, first.image_filename);
+    assert_eq!(images().len(), 2);
+}
+
+#[tokio::test]
+async fn follows_next_links() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    // No numeric pager, relative links resolve against the current page
+    let pages = [
+        ("/cat/", "/p1/", Some("/cat/page/2/")),
+        ("/cat/page/2/", "/p2/", Some("../3/")),
+        ("/cat/page/3/", "/p3/", None),
+    ];
+    for (path, plant, next) in pages {
+        let next = next
+            .map(|next| format!(r#"<a rel="next" href="{}">Далее</a>"#, next))
+            .unwrap_or_default();
+        let html = category_page(&[plant]).replace("</body>", &format!("{}</body>", next));
+        server.route(path, Response::html(&html));
+    }
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    let urls = scraper.collect_urls().await.unwrap();
+    assert_eq!(
+        urls,
+        [server.url("/p1/"), server.url("/p2/"), server.url("/p3/")]
+    );
+    assert_eq!(server.hits("/cat/page/3/"), 1);
+}
+
+#[tokio::test]
+async fn deadline_stops_category_pagination() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+    let pager = r#"<div class="nav-links"><a>1</a><a>2</a><a>3</a><a>4</a></div>"#;
+    let html = category_page(&["/p1/"]).replace("</body>", &format!("{}</body>", pager));
+    server.route("/cat/", Response::html(&html));
+    for page in 1..=4 {
+        let list_page = Response::html(&category_page(&["/p1/"]));
+        server.route(
+            &format!("/cat/page/{}", page),
+            list_page.delayed(Duration::from_millis(300)),
+        );
+    }
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_concurrent_tasks(1)
+        .with_deadline(Duration::from_millis(450));
+
+    let report = scraper.scraper_with_report().await.unwrap();
+    assert!(report.timed_out);
+    // The page in flight at the deadline is finished, no new one starts
+    assert_eq!(server.hits("/cat/page/2"), 1);
+    assert_eq!(server.hits("/cat/page/3"), 0);
+    assert_eq!(server.hits("/cat/page/4"), 0);
+}
+
+#[tokio::test]
+async fn database_errors_do_not_stop_the_scrape() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    let scraper = Scraper {
+        database: Some(FlakyDatabase::failing_on("p2")),
+        ..site_scraper(&server)
+    };
+
+    let report = scraper.scraper_with_report().await.unwrap();
+    assert_eq!(report.plants.len(), 3);
+    assert_eq!(report.stats.db_failed, 1);
+    assert_eq!(report.db_failures[0].0, server.url("/p2/"));
+    assert!(matches!(report.db_failures[0].1, ScrapeError::Database(_)));
+    let stored = scraper.database.unwrap().get_all().await.unwrap();
+    assert_eq!(stored.len(), 2);
+}
+
+#[test]
+fn display_snapshot() {
+    let mut plant = sample_plant("  Фикус Бенджамина ", "https://example.com/ficus/");
+    plant.attributes.humidity = None;
+    plant.attributes.soil = None;
+    let expected = "\
+Фикус Бенджамина
+Изображение: abc.jpg
+Температура: 18–24 °C
+Освещение: яркий рассеянный свет
+Полив: летом обильный, зимой умеренный
+Подкормка: раз в две недели
+Пересадка: весной
+Размножение: черенками
+Особенности: ядовит
+";
+    assert_eq!(plant.to_string(), expected);
+
+    // Not downloaded yet
+    plant.image_filename = None;
+    let text = plant.to_string();
+    assert_eq!(
+        text.lines().nth(1),
+        Some("Изображение: https://example.com/abc.jpg")
+    );
+}
+
+#[tokio::test]
+async fn parses_gzip_encoded_pages() {
+    use std::io::Write;
+
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let html = plant_page("Фикус", "/img/ficus.jpg", &CARE_ROWS);
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(html.as_bytes()).unwrap();
+    let gzip = encoder.finish().unwrap();
+    server.route(
+        "/ficus/",
+        Response::bytes("text/html; charset=UTF-8", gzip).header("Content-Encoding", "gzip"),
+    );
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    let plant = scraper.scrape_single(&server.url("/ficus/")).await.unwrap();
+    assert_eq!(plant.name, "Фикус");
+    assert_eq!(plant.attributes.watering, attribute("Полив", "умеренный"));
+}
+
+#[tokio::test]
+async fn unwritable_image_dir_is_an_error() {
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let dir = tempfile::tempdir().unwrap();
+    // A directory can't be created inside a regular file, even by root
+    let file = dir.path().join("file");
+    std::fs::write(&file, "").unwrap();
+    let image_dir = file.join("images");
+    let scraper = TestScraper::new(1, image_dir.to_str().unwrap(), None)
+        .with_base_url(&server.url("/"))
+        .with_max_retries(0);
+
+    let result = scraper.scrape_single(&server.url("/ficus/")).await;
+    let err = match result {
+        Err(ScrapeError::ImageDownload(err)) => err,
+        other => panic!("expected an image error, got {:?}", other),
+    };
+    let message = format!("{:#}", err);
+    assert!(message.contains(image_dir.to_str().unwrap()), "{}", message);
+}
+
+#[cfg(feature = "images")]
+#[tokio::test]
+async fn thumbnail_fits_bounds_and_keeps_original() {
+    use image::GenericImageView;
+
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(200, 100))
+        .write_to(&mut png, image::ImageFormat::Png)
+        .unwrap();
+    server.route("/img/ficus.jpg", Response::bytes("image/png", png.clone()));
+    let dir = tempfile::tempdir().unwrap();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_images(true)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()))
+        .with_thumbnail(50, 50);
+
+    let plant = scraper.scrape_single(&server.url("/ficus/")).await.unwrap();
+    let original = std::fs::read(dir.path().join(plant.image_filename.unwrap())).unwrap();
+    assert_eq!(original, png);
+    let thumbnail = plant.thumbnail.unwrap();
+    assert!(thumbnail.ends_with("_50x50.png"));
+    let thumbnail = image::open(dir.path().join(thumbnail)).unwrap();
+    assert_eq!(thumbnail.dimensions(), (50, 25));
+}
+
+#[test]
+fn dedup_by_name_keeps_most_complete() {
+    let mut sparse = sample_plant("Фикус  Бенджамина", "https://x/ficus-1/");
+    sparse.attributes.soil = None;
+    let complete = sample_plant("фикус бенджамина", "https://x/ficus-2/");
+    let first = sample_plant("Алоэ", "https://x/aloe-1/");
+    let tie = sample_plant("алоэ", "https://x/aloe-2/");
+
+    let plants = dedup_by_name(vec![sparse, first, complete, tie]);
+    let urls = plants
+        .iter()
+        .map(|plant| plant.source_url.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(urls, ["https://x/ficus-2/", "https://x/aloe-1/"]);
+}
+
+#[tokio::test]
+async fn scrape_category_follows_its_pager() {
+    let server = test_site(&[
+        ("sukkulenty", &["aloe", "agave", "haworthia"]),
+        ("palmy", &["hamedorea"]),
+    ])
+    .await;
+    let pager = r#"<div class="nav-links"><span class="page-numbers current">1</span>
+        <a class="page-numbers" href="/sukkulenty/page/2">2</a></div>"#;
+    let first = category_page(&["/aloe/", "/agave/"]);
+    let first = first.replace("</body>", &format!("{}</body>", pager));
+    server.route("/sukkulenty/", Response::html(&first));
+    server.route("/sukkulenty/page/1", Response::html(&first));
+    server.route(
+        "/sukkulenty/page/2",
+        Response::html(&category_page(&["/haworthia/", "/aloe/"])),
+    );
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    let plants = scraper.scrape_category("/sukkulenty/").await.unwrap();
+    let names = plants
+        .iter()
+        .map(|plant| plant.name.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["agave", "aloe", "haworthia"]);
+    assert_eq!(server.hits("/aloe/"), 1);
+    assert_eq!(server.hits("/palmy/"), 0);
+}
+
+#[test]
+fn clean_text_decodes_leftover_entities() {
+    assert_eq!(clean_text("18&ndash;24&nbsp;&deg;C"), "18–24 °C");
+    assert_eq!(clean_text("Полив &laquo;снизу&raquo;"), "Полив «снизу»");
+    assert_eq!(clean_text("без сущностей"), "без сущностей");
+}
+
+#[test]
+fn collapses_messy_whitespace() {
+    assert_eq!(clean_text("  Полив\n\t  умеренный \r\n"), "Полив умеренный");
+    assert_eq!(clean_text(" \n\t "), "");
+
+    let rows = [
+        ("\n  Температура\n", "\t18–24 °C  \n"),
+        ("Полив", "летом\n\n   обильный"),
+    ];
+    let html = plant_page("\n   Алоэ   вера \n", "/a.jpg", &rows);
+    let plant = TestScraper::default()
+        .parse_houseplant_html(&html, "https://example.com/aloe/")
+        .unwrap();
+    assert_eq!(plant.name, "Алоэ вера");
+    assert_eq!(
+        plant.attributes.temperature,
+        attribute("Температура", "18–24 °C")
+    );
+    assert_eq!(
+        plant.attributes.watering,
+        attribute("Полив", "летом обильный")
+    );
+}
+
+#[tokio::test]
+async fn title_page_without_categories_is_an_error() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+    server.route(
+        "/",
+        Response::html("<html><body><p>Новый дизайн</p></body></html>"),
+    );
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    let result = scraper.scraper().await;
+    assert!(matches!(result, Err(ScrapeError::MissingElement { what }) if what == "categories"));
+    assert_eq!(server.hits("/cat/"), 0);
+}
+
+#[tokio::test]
+async fn categories_without_plant_links_are_an_error() {
+    let server = test_site(&[("cat", &["p1"]), ("other", &["p2"])]).await;
+    // List pages of a changed design, without `itemprop="url"` links
+    let redesigned = "<html><body><a href=\"/p1/\">Фикус</a></body></html>";
+    for path in ["/cat/", "/cat/page/1", "/other/", "/other/page/1"] {
+        server.route(path, Response::html(redesigned));
+    }
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    let result = scraper.scraper().await;
+    assert!(matches!(result, Err(ScrapeError::MissingElement { what }) if what == "plant URLs"));
+    assert_eq!(server.hits("/p1/"), 0);
+}
+
+#[cfg(feature = "cookies")]
+#[tokio::test]
+async fn keeps_session_cookie() {
+    let server = test_site(&[("cat", &["p1"])]).await;
+    server.route(
+        "/",
+        Response::html(&title_page(&["/cat/"])).header("Set-Cookie", "session=abc; Path=/"),
+    );
+    // Later pages are only served within the session
+    let pages = [
+        ("/cat/page/1", category_page(&["/p1/"])),
+        ("/p1/", plant_page("p1", "/img/p1.jpg", &CARE_ROWS)),
+    ];
+    for (path, html) in pages {
+        server.route_fn(path, move |request| match request.header("Cookie") {
+            Some(cookie) if cookie.contains("session=abc") => Response::html(&html),
+            _ => Response::status(403),
+        });
+    }
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+    assert!(scraper.scraper().await.is_err());
+    let plants = scraper.with_cookies(true).scraper().await.unwrap();
+    assert_eq!(plants.len(), 1);
+    assert_eq!(plants[0].name, "p1");
+}
+
+#[tokio::test]
+async fn fail_fast_returns_first_error() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3", "p4"])]).await;
+    server.route(
+        "/p1/",
+        Response::html("<html><body>Без заголовка</body></html>"),
+    );
+    server.route(
+        "/p2/",
+        Response::html(&plant_page("p2", "/img/p2.jpg", &[])),
+    );
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_concurrent_tasks(1)
+        .with_fail_fast(true);
+
+    let result = scraper.scraper().await;
+    assert!(matches!(result, Err(ScrapeError::MissingElement { what }) if what == "title"));
+    assert_eq!(server.hits("/p3/") + server.hits("/p4/"), 0);
+
+    // Without it the failures are only reported
+    let report = scraper
+        .with_fail_fast(false)
+        .scraper_with_report()
+        .await
+        .unwrap();
+    assert_eq!(report.plants.len(), 2);
+    assert_eq!(report.failures.len(), 2);
+}
+
+#[tokio::test]
+async fn page_count_is_capped() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    let pager = r#"<div class="nav-links"><span class="page-numbers current">1</span>
+        <a class="page-numbers" href="/cat/page/100000">100000</a></div>"#;
+    let first = category_page(&["/p1/"]).replace("</body>", &format!("{}</body>", pager));
+    server.route("/cat/", Response::html(&first));
+    server.route("/cat/page/1", Response::html(&first));
+    server.route("/cat/page/2", Response::html(&category_page(&["/p2/"])));
+    server.route("/cat/page/3", Response::html(&category_page(&["/p3/"])));
+    let scraper = site_scraper::<InMemoryDatabase>(&server).with_max_pages_per_category(2);
+
+    let urls = scraper.collect_urls().await.unwrap();
+    assert_eq!(urls, [server.url("/p1/"), server.url("/p2/")]);
+    assert_eq!(server.hits("/cat/page/3"), 0);
+}
+
+#[tokio::test]
+async fn image_naming_strategies() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    // Two names with the same slug, p3 shows the image of p1
+    let pages = [
+        ("p1", "Фикус Бенджамина", "/img/p1.jpg"),
+        ("p2", "фикус, бенджамина!", "/img/p2.jpg"),
+        ("p3", "Фикус Бенджамина", "/img/p1.jpg"),
+    ];
+    for (path, title, image) in pages {
+        let page = plant_page(title, image, &CARE_ROWS);
+        server.route(&format!("/{}/", path), Response::html(&page));
+    }
+    let names = |naming| {
+        let server = &server;
+        async move {
+            let dir = tempfile::tempdir().unwrap();
+            let scraper = site_scraper::<InMemoryDatabase>(server)
+                .with_concurrent_tasks(1)
+                .with_images(true)
+                .with_image_naming(naming)
+                .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()));
+            let plants = scraper.scraper().await.unwrap();
+            let files = std::fs::read_dir(dir.path()).unwrap().count();
+            let names = plants
+                .into_iter()
+                .map(|plant| plant.image_filename.unwrap())
+                .collect::<Vec<_>>();
+            (names, files)
+        }
+    };
+
+    let (hashes, files) = names(ImageNaming::ContentHash).await;
+    let p1_hash = format!("{:x}.jpg", Sha256::digest(&fake_jpeg("p1")));
+    let p2_hash = format!("{:x}.jpg", Sha256::digest(&fake_jpeg("p2")));
+    assert_eq!(hashes, [p1_hash.clone(), p2_hash, p1_hash]);
+    assert_eq!(files, 2);
+
+    let (slugs, files) = names(ImageNaming::PlantSlug).await;
+    assert_eq!(
+        slugs,
+        [
+            "фикус-бенджамина.jpg",
+            "фикус-бенджамина-2.jpg",
+            "фикус-бенджамина.jpg"
+        ]
+    );
+    assert_eq!(files, 2);
+
+    let (timestamps, files) = names(ImageNaming::Timestamp).await;
+    assert!(timestamps.iter().all(|name| {
+        let stem = name.split(['-', '.']).next().unwrap();
+        name.ends_with(".jpg") && stem.parse::<u128>().is_ok()
+    }));
+    // Every download gets its own file
+    let mut unique = timestamps.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(unique.len(), 3);
+    assert_eq!(files, 3);
+}
+
+#[tokio::test]
+async fn database_is_flushed_after_scrape_and_cancel() {
+    let server = test_site(&[("cat", &["p1", "p2"])]).await;
+    let scraper = Scraper {
+        database: Some(FlakyDatabase::default()),
+        ..site_scraper(&server)
+    }
+    .with_batch_size(10);
+    let flushes = || {
+        scraper
+            .database
+            .as_ref()
+            .unwrap()
+            .flushes
+            .load(Ordering::SeqCst)
+    };
+
+    scraper.scraper().await.unwrap();
+    assert_eq!(flushes(), 1);
+
+    let token = CancellationToken::new();
+    token.cancel();
+    scraper.scraper_cancellable(token).await.unwrap();
+    assert_eq!(flushes(), 2);
+}
+
+#[test]
+fn tag_pages_are_rejected() {
+    let scraper = TestScraper::default();
+    let url = "https://example.com/tag/polivka/";
+    // Tag page listing articles, one of them quotes a watering tip
+    let tag_page = r#"<html><body>
+        <h1 class="entry-title">Метка: полив</h1>
+        <img itemprop="url image" data-src="/logo.png">
+        <a itemprop="url" href="/kak-polivat/">Как поливать</a>
+        <table><tbody><tr><td>Полив</td><td>раз в неделю</td></tr></tbody></table>
+        </body></html>"#;
+    let result = scraper.parse_houseplant_html(tag_page, url);
+    assert!(
+        matches!(result, Err(ScrapeError::Parse(message)) if message.starts_with("Not a plant page"))
+    );
+
+    let without_table = tag_page.replace("Полив", "Автор");
+    let result = scraper.parse_houseplant_html(&without_table, url);
+    assert!(matches!(result, Err(ScrapeError::MissingElement { what }) if what == "plant table"));
+
+    // Two known rows are enough
+    let rows = [("Полив", "умеренный"), ("Освещение", "яркое")];
+    let html = plant_page("Алоэ", "/a.jpg", &rows);
+    assert!(scraper.parse_houseplant_html(&html, url).is_ok());
+}
+
+#[tokio::test]
+async fn database_only_mode_keeps_no_plants() {
+    let server = test_site(&[("cat", &["p1", "p2", "p3"])]).await;
+    let scraper = Scraper {
+        database: Some(InMemoryDatabase::default()),
+        ..site_scraper(&server)
+    };
+
+    let report = scraper.scrape_to_database_only().await.unwrap();
+    assert!(report.plants.is_empty());
+    assert_eq!(report.stats.parsed, 3);
+    let stored = scraper.database.unwrap().get_all().await.unwrap();
+    let mut names = stored
+        .iter()
+        .map(|plant| plant.name.as_str())
+        .collect::<Vec<_>>();
+    names.sort_unstable();
+    assert_eq!(names, ["p1", "p2", "p3"]);
+
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+    let result = scraper.scrape_to_database_only().await;
+    assert!(matches!(result, Err(ScrapeError::Database(_))));
+}
+
+#[test]
+fn repeated_attribute_rows_are_merged() {
+    capture_logs();
+    let rows = [
+        ("Температура", "18–24 °C"),
+        ("Полив", "умеренный"),
+        ("Температура", "18–24 °C, зимой не ниже 15"),
+        ("Полив зимой", "редкий"),
+        ("Полив", "умеренный"),
+    ];
+    let html = plant_page("Алоэ", "/a.jpg", &rows);
+    let plant = TestScraper::default()
+        .parse_houseplant_html(&html, "https://example.com/aloe/")
+        .unwrap();
+
+    // The more informative value wins, different ones are joined
+    assert_eq!(
+        plant.attributes.temperature,
+        attribute("Температура", "18–24 °C, зимой не ниже 15")
+    );
+    assert_eq!(
+        plant.attributes.watering,
+        attribute("Полив", "умеренный; редкий")
+    );
+    assert!(!logged("Повторяющийся атрибут watering").is_empty());
+}
+
+#[test]
+fn bullets_only_in_features() {
+    let rows = [
+        ("Температура", "18–24 °C"),
+        (
+            "Полив",
+            "<ul><li>летом обильный</li><li>зимой редкий</li></ul>",
+        ),
+        (
+            "Особенности",
+            "<ul><li>ядовит</li><li>цветёт редко</li></ul>",
+        ),
+    ];
+    let html = plant_page("Алоэ", "/a.jpg", &rows);
+    let plant = TestScraper::default()
+        .parse_houseplant_html(&html, "https://example.com/aloe/")
+        .unwrap();
+
+    assert_eq!(
+        plant.attributes.watering,
+        attribute("Полив", "летом обильный зимой редкий")
+    );
+    assert_eq!(
+        plant.attributes.features,
+        attribute("Особенности", "• ядовит • цветёт редко")
+    );
+}
+
+#[cfg(feature = "images")]
+#[tokio::test]
+async fn output_format_reencodes_stored_images() {
+    let server = test_site(&[("cat", &["ficus"])]).await;
+    let mut png = Vec::new();
+    image::DynamicImage::ImageRgb8(image::RgbImage::new(40, 30))
+        .write_to(&mut png, image::ImageFormat::Png)
+        .unwrap();
+    server.route("/img/ficus.jpg", Response::bytes("image/png", png));
+    let dir = tempfile::tempdir().unwrap();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_images(true)
+        .with_image_store(FsImageStore::new(dir.path().to_str().unwrap()))
+        .with_output_format(OutputFormat::Jpeg);
+
+    let plant = scraper.scrape_single(&server.url("/ficus/")).await.unwrap();
+    let name = plant.image_filename.unwrap();
+    assert!(name.ends_with(".jpg"), "{}", name);
+    let stored = std::fs::read(dir.path().join(name)).unwrap();
+    assert_eq!(sniff_image(&stored), Some("jpg"));
+    assert_eq!(
+        image::guess_format(&stored).unwrap(),
+        image::ImageFormat::Jpeg
+    );
+    let stored = image::load_from_memory(&stored).unwrap();
+    assert_eq!(image::GenericImageView::dimensions(&stored), (40, 30));
+}
+
+#[tokio::test]
+async fn progress_counts_finished_tasks() {
+    let server = test_site(&[("cat", &["p1", "p2"])]).await;
+    let delay = Duration::from_millis(300);
+    let category = category_page(&["/p1/", "/p2/"]);
+    server.route("/cat/", Response::html(&category).delayed(delay));
+    let page = plant_page("p1", "/img/p1.jpg", &CARE_ROWS);
+    server.route("/p1/", Response::html(&page).delayed(delay));
+    let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let started = std::time::Instant::now();
+    let scraper = site_scraper::<InMemoryDatabase>(&server)
+        .with_concurrent_tasks(2)
+        .with_progress_callback(move |event| {
+            recorded.lock().unwrap().push((event, started.elapsed()));
+        });
+
+    scraper.scraper().await.unwrap();
+    let events = events.lock().unwrap();
+    let at = |expected: ProgressEvent| {
+        events
+            .iter()
+            .find(|(event, _)| *event == expected)
+            .map(|(_, elapsed)| *elapsed)
+            .unwrap()
+    };
+    let category_done = at(ProgressEvent::CategoryDone {
+        current: 1,
+        total: 1,
+    });
+    assert!(category_done >= delay);
+    // p2 finishes first, p1 only after its delay
+    let first_plant = at(ProgressEvent::PlantDone {
+        current: 1,
+        total: 2,
+    });
+    let all_plants = at(ProgressEvent::PlantDone {
+        current: 2,
+        total: 2,
+    });
+    assert!(first_plant < category_done + delay);
+    assert!(all_plants >= category_done + delay);
+}
+
+#[tokio::test]
+async fn error_status_is_an_http_error() {
+    let server = test_site(&[("cat", &["p1", "p2"])]).await;
+    server.route("/p1/", Response::status(404));
+    server.route("/p2/", Response::status(500));
+    let scraper = site_scraper::<InMemoryDatabase>(&server);
+
+    for (path, status) in [("/p1/", 404), ("/p2/", 500)] {
+        match scraper.scrape_single(&server.url(path)).await {
+            Err(ScrapeError::Http(err)) => assert_eq!(err.status().unwrap().as_u16(), status),
+            other => panic!("expected an HTTP error, got {:?}", other),
+        }
+    }
+}
+
+// Another site, one "parameter: value" paragraph per attribute
+struct ParagraphAdapter;
+
+impl SiteAdapter for ParagraphAdapter {
+    fn category_urls(&self, _html: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn page_url(&self, category_url: &str, _page: usize) -> String {
+        category_url.to_string()
+    }
+
+    fn plant_urls(&self, _html: &str) -> Vec<String> {
+        Vec::new()
+    }
+
+    fn parse_plant(&self, html: &str, _url: &str) -> Result<adapter::PlantPage> {
+        let mut lines = html.lines().map(str::trim).filter(|line| !line.is_empty());
+        let name = lines.next().unwrap_or_default().to_string();
+        let rows = lines
+            .filter_map(|line| line.split_once(':'))
+            .map(|(parameter, value)| adapter::PlantRow {
+                parameter: parameter.to_string(),
+                value: value.to_string(),
+                features_value: None,
+            })
+            .collect();
+        Ok(adapter::PlantPage {
+            name,
+            image_url: "/a.jpg".to_string(),
+            rows,
+        })
+    }
+}
+
+#[test]
+fn attribute_minimum_belongs_to_the_adapter() {
+    let page = "Алоэ\nПолив: умеренный";
+    let scraper = TestScraper::default().with_adapter(ParagraphAdapter);
+    let plant = scraper
+        .parse_houseplant_html(page, "https://example.org/aloe")
+        .unwrap();
+    assert_eq!(plant.attributes.watering, attribute("Полив", "умеренный"));
+}
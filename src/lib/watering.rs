@@ -0,0 +1,76 @@
+use crate::Attribute;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WateringLevel {
+    Sparse,
+    Moderate,
+    Abundant,
+}
+
+// Word stems of each level
+const KEYWORDS: &[(&str, WateringLevel)] = &[
+    ("скудн", WateringLevel::Sparse),
+    ("редк", WateringLevel::Sparse),
+    ("огранич", WateringLevel::Sparse),
+    ("минимальн", WateringLevel::Sparse),
+    ("сокращ", WateringLevel::Sparse),
+    ("слаб", WateringLevel::Sparse),
+    ("умерен", WateringLevel::Moderate),
+    ("средн", WateringLevel::Moderate),
+    ("обильн", WateringLevel::Abundant),
+    ("частый", WateringLevel::Abundant),
+    ("часто", WateringLevel::Abundant),
+    ("интенсивн", WateringLevel::Abundant),
+    ("постоянно влажн", WateringLevel::Abundant),
+];
+
+impl Attribute {
+    /// Coarse watering level from a value like "летом обильный, зимой
+    /// умеренный". The first mentioned level wins, which is the growing
+    /// season on most pages.
+    pub fn watering_level(&self) -> Option<WateringLevel> {
+        let text = self.value.to_lowercase();
+        KEYWORDS
+            .iter()
+            .filter_map(|(stem, level)| text.find(stem).map(|pos| (pos, *level)))
+            .min_by_key(|(pos, _)| *pos)
+            .map(|(_, level)| level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::attribute;
+
+    fn level(value: &str) -> Option<WateringLevel> {
+        attribute("Полив", value).unwrap().watering_level()
+    }
+
+    #[test]
+    fn classifies_real_phrasings() {
+        assert_eq!(
+            level("летом обильный, зимой умеренный"),
+            Some(WateringLevel::Abundant)
+        );
+        assert_eq!(level("Умеренный"), Some(WateringLevel::Moderate));
+        assert_eq!(
+            level("редкий, после полного просыхания грунта"),
+            Some(WateringLevel::Sparse)
+        );
+        assert_eq!(
+            level("Зимой ограниченный, летом обильный"),
+            Some(WateringLevel::Sparse)
+        );
+        assert_eq!(
+            level("грунт должен быть постоянно влажным"),
+            Some(WateringLevel::Abundant)
+        );
+    }
+
+    #[test]
+    fn unclassifiable_is_none() {
+        assert_eq!(level("по мере подсыхания верхнего слоя"), None);
+        assert_eq!(level(""), None);
+    }
+}